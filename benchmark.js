@@ -289,6 +289,45 @@ benchmark('Decode - Nested message', () => {
   r.uint32();
 });
 
+console.log('\n=== Large Buffers (1MB, mixed fields) ===\n');
+
+function encodeLargeMessage() {
+  const w = new Writer();
+  const payload = Buffer.alloc(64, 0xab);
+  while (w.len() < 1024 * 1024) {
+    w.uint32(8).uint32(150);
+    w.uint32(17).fixed64(123456789);
+    w.uint32(26).bytes(payload);
+    w.uint32(34).string('mixed field payload');
+  }
+  return w.finish();
+}
+
+const largeBuf = encodeLargeMessage();
+
+// The Reader reads the caller's Buffer in place. It used to copy the whole input on
+// construction, one 1MB allocation per Reader here; now construction allocates nothing
+benchmark('Reader construction - 1MB', () => {
+  new Reader(largeBuf);
+}, 10000);
+
+benchmark('Decode - 1MB mixed fields', () => {
+  const r = new Reader(largeBuf);
+  while (r.remaining() > 0) {
+    r.skipType(r.tag().wireType);
+  }
+}, 100);
+
+// Keep the Readers alive so a copied input would show up as resident memory. The copy lived
+// on the Rust heap, which process.memoryUsage().external does not count, so measure RSS
+const retainedReaders = [];
+const rssBefore = process.memoryUsage().rss;
+for (let i = 0; i < 64; i++) {
+  retainedReaders.push(new Reader(largeBuf));
+}
+const rssPerReader = Math.max(0, (process.memoryUsage().rss - rssBefore) / retainedReaders.length);
+console.log(`\nMemory retained per Reader over ${formatNumber(largeBuf.length)} bytes: ${formatNumber(Math.round(rssPerReader))} bytes`);
+
 console.log('\n=== Throughput Analysis ===\n');
 
 const complexMsgSize = complexBuf.length;
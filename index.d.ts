@@ -5,6 +5,18 @@
 
 /** Encode a varint value to a Buffer */
 export declare function encodeVarint(value: number): Buffer
+/**
+ * Encode a uint32 as a varint padded to exactly 5 bytes
+ * Useful as a fixed-width placeholder that can be backfilled in place
+ */
+export declare function encodeVarintPadded(value: number): Buffer
+/** Number of bytes encodeVarint would produce for a uint32, computed without encoding */
+export declare function varintLength32(value: number): number
+/**
+ * Number of bytes a 64-bit varint occupies, treating the value's bits as unsigned
+ * (negative values take 10 bytes), computed without encoding
+ */
+export declare function varintLength64(value: number): number
 /**
  * Decode a varint from a buffer at the given position
  * Returns an object with { value, length }
@@ -14,10 +26,269 @@ export interface VarintResult {
   length: number
 }
 export declare function decodeVarint(buffer: Buffer, pos: number): VarintResult
+/**
+ * Decode a varint from a stream buffer at the given position
+ * Returns null if the buffer ends part-way through the varint (ERR_NEED_MORE_DATA) and
+ * throws ERR_VARINT_OVERFLOW if the varint is longer than 10 bytes or overflows 64 bits
+ */
+export declare function tryDecodeVarint(buffer: Buffer, pos: number): VarintResult | null
+/**
+ * Check whether the bytes from `offset` form a complete, structurally valid message
+ * Returns the message length, or null if the buffer ends mid-field and more data is needed.
+ * Throws if the bytes can never form a valid message
+ */
+export declare function tryParseMessage(buffer: Buffer, offset: number): number | null
+/** Compute the CRC-32 (IEEE) checksum of a buffer */
+export declare function crc32(buffer: Buffer): number
+/**
+ * Compare two buffers byte for byte
+ * With `constant_time` the time taken depends only on the lengths, not on where the first
+ * difference is, for comparing secrets such as MACs or tokens
+ */
+export declare function bytesEqual(a: Buffer, b: Buffer, constantTime?: boolean | undefined | null): boolean
+/** Check whether a buffer begins with the bytes of `prefix` */
+export declare function bytesStartsWith(buffer: Buffer, prefix: Buffer): boolean
+/**
+ * Rewrite a repeated numeric field between packed and unpacked encodings
+ * All other fields are copied through unchanged. When unpacking, `element_wire_type`
+ * selects how the packed payload is split into elements (varint by default)
+ */
+export declare function repackField(buffer: Buffer, fieldNumber: number, toPacked: boolean, elementWireType?: number | undefined | null): Buffer
+/**
+ * Re-encode a message canonically so logically equal messages have identical bytes
+ * Varints are minimized and fields are stably sorted by field number (repeated values keep
+ * their order). Length-delimited payloads are copied verbatim unless `depth` (default 0) is
+ * given: then payloads that parse as messages are canonicalized too, down to that many
+ * levels. Without a schema a string or bytes value can look like a message, so only
+ * recurse when such values cannot occur at those levels
+ */
+export declare function canonicalizeMessage(buffer: Buffer, depth?: number | undefined | null): Buffer
+/**
+ * Re-encode every varint in a message minimally (tags, length prefixes and varint values)
+ * without reordering anything, unlike canonicalizeMessage. Length-delimited payloads are
+ * copied verbatim unless `depth` (default 0) is given: then payloads that parse as messages
+ * are minimized too, down to that many levels. Without a schema a string or bytes value can
+ * look like a message, so only recurse when such values cannot occur at those levels
+ */
+export declare function minimizeVarints(buffer: Buffer, depth?: number | undefined | null): Buffer
+/**
+ * Hash a message independently of field order and varint encoding
+ * `algorithm` is "sha256" (default) or "fnv1a64". The message is canonicalized first, with
+ * `depth` passed to canonicalizeMessage, so nested messages only hash independently of
+ * their encoding when `depth` covers them
+ */
+export declare function messageHash(buffer: Buffer, algorithm?: string | undefined | null, depth?: number | undefined | null): Buffer
+/** A top-level field that differs between two messages */
+export interface FieldDiff {
+  fieldNumber: number
+  /** Whether the field occurs in the first message */
+  inA: boolean
+  /** Whether the field occurs in the second message */
+  inB: boolean
+}
+/**
+ * Report the top-level fields whose presence or raw encoding differs between two messages
+ * Repeated fields are compared by all their occurrences in order. Results are sorted by
+ * field number; fields present in both with different bytes have `inA` and `inB` set
+ */
+export declare function diffMessages(a: Buffer, b: Buffer): Array<FieldDiff>
+/**
+ * Re-emit a message with selected top-level fields rewritten by JS callbacks, keyed by field
+ * number in `transforms`. Each callback receives a field's value bytes (without tag or
+ * length prefix) and returns replacement value bytes, or null to drop the field; the tag,
+ * and the length prefix for length-delimited fields, are written natively. Fields without
+ * a callback are copied byte for byte. Callbacks run once per occurrence, in order. A group's
+ * value is its body followed by the matching EndGroup tag, and a replacement must end the same way
+ */
+export declare function transformMessage(buffer: Buffer, transforms: object): Buffer
+/** Decoded Thrift compact field header */
+export interface ThriftFieldHeader {
+  fieldId: number
+  fieldType: number
+  length: number
+}
+/** Encode a signed integer as a Thrift compact zigzag varint */
+export declare function thriftEncodeVarint(value: number): Buffer
+/** Decode a Thrift compact zigzag varint from a buffer at the given position */
+export declare function thriftDecodeVarint(buffer: Buffer, pos: number): VarintResult
+/** Encode a Thrift compact field header relative to the previous field id */
+export declare function thriftEncodeFieldHeader(fieldId: number, lastFieldId: number, fieldType: number): Buffer
+/**
+ * Decode a Thrift compact field header from a buffer at the given position
+ * A STOP header returns field id 0 and field type 0
+ */
+export declare function thriftDecodeFieldHeader(buffer: Buffer, pos: number, lastFieldId: number): ThriftFieldHeader
+/** A field in a JS schema description */
+export interface FieldDescriptor {
+  /** Property name on the JS object */
+  name: string
+  fieldNumber: number
+  /** `.proto` type name ("int32", "string", "message", ...) */
+  type: string
+  repeated?: boolean
+  /** Whether a repeated numeric or bool field is packed (defaults to true, as in proto3) */
+  packed?: boolean
+  /** Fields of the nested message when `type` is "message" */
+  fields?: Array<FieldDescriptor>
+  /** Enum value numbers by name when `type` is "enum", used by the JSON converters */
+  enumValues?: Record<string, number>
+}
+/**
+ * Compile a schema description once for repeated encoding and decoding
+ * With `reject_unknown_fields` set, decoding throws ERR_UNKNOWN_FIELD for any field number
+ * not in the schema (at any nesting level) instead of skipping it
+ */
+export declare function compileSchema(schema: Array<FieldDescriptor>, rejectUnknownFields?: boolean | undefined | null): SchemaHandle
+/**
+ * Encode a JS object using a compiled schema
+ * Missing, null and undefined properties are omitted; 64-bit fields accept numbers or BigInts.
+ * Numbers that are fractional, non-finite or out of range for an integer field throw ERR_RANGE.
+ * Empty strings and bytes are written as present (length 0) unless `skip_empty` is set, which
+ * omits them as proto3 does for fields without explicit presence. `unknowns` holds raw fields
+ * (tags and values) preserved from an earlier decode; each is spliced in before the first
+ * known field with a higher number, so unknown fields survive a decode/modify/encode cycle
+ */
+export declare function encodeWith(handle: SchemaHandle, obj: object, skipEmpty?: boolean | undefined | null, unknowns?: Buffer | undefined | null): Buffer
+/**
+ * Exact size in bytes of encodeWith(handle, obj, skipEmpty) without encoding, including
+ * nested messages' tags and length prefixes
+ */
+export declare function encodedSize(handle: SchemaHandle, obj: object, skipEmpty?: boolean | undefined | null): number
+/**
+ * Compare two encoded messages field by field after decoding both with a compiled schema
+ * Field order, non-minimal varints, packed vs unpacked repeated fields and omitted defaults
+ * (0, false, "", empty) do not make messages unequal; unknown fields are ignored
+ */
+export declare function messagesEqual(a: Buffer, b: Buffer, handle: SchemaHandle): boolean
+/**
+ * Decode a buffer into a JS object using a compiled schema
+ * Unknown fields are skipped; 64-bit values above 2^53 - 1 are returned as BigInts
+ */
+export declare function decodeWith(handle: SchemaHandle, buffer: Buffer): object
+/**
+ * Decode the message in the `length` bytes at `offset` (by default everything after
+ * `offset`), returning `{ value, bytesConsumed }` so the caller can advance its cursor.
+ * Protobuf messages are not self-delimiting: concatenated messages parse as a single merged
+ * message, so the end must come from the caller, e.g. a length prefix or a known size
+ */
+export declare function decodeWithLength(handle: SchemaHandle, buffer: Buffer, offset?: number | undefined | null, length?: number | undefined | null): { value: object, bytesConsumed: number }
+/**
+ * Decode only the singular numeric and bool fields of a message into an existing object
+ * String, bytes, message and repeated fields are skipped, so no JS strings, buffers or
+ * arrays are allocated. Absent fields are reset to 0 (or false) so values never leak
+ * from a previous message decoded into the same target
+ */
+export declare function decodeScalarsInto(handle: SchemaHandle, buffer: Buffer, target: object): void
+/**
+ * Decode a flat message of scalar fields numbered 1 to 15, whose tags are all one byte
+ * Each tag byte is looked up directly and matching values are decoded in place without
+ * scanning the field first. Throws if the schema has string, bytes or message fields, or
+ * if a multi-byte tag appears (use decodeWith for those messages)
+ */
+export declare function decodeFlatSmall(handle: SchemaHandle, buffer: Buffer): object
+/**
+ * Decode a batch of messages into one typed array per singular numeric or bool field
+ * Element i of each column comes from message i; absent fields contribute the type's
+ * default. 64-bit types use BigInt64Array/BigUint64Array and bools use Uint8Array.
+ * Repeated, string, bytes and message fields are not included
+ */
+export declare function decodeBatchColumnar(buffers: Array<Buffer>, handle: SchemaHandle): object
+/**
+ * Decode a buffer with a compiled schema and serialize it as a proto3 JSON string
+ * Only fields present on the wire are written; unknown enum values are written as numbers
+ */
+export declare function protoToJson(handle: SchemaHandle, buffer: Buffer): string
+/**
+ * Parse a proto3 JSON string and encode it with a compiled schema
+ * Fields are matched by JSON (lowerCamelCase) or original name and null means absent.
+ * Unknown fields throw unless `ignore_unknown_fields` is set
+ */
+export declare function jsonToProto(handle: SchemaHandle, json: string, ignoreUnknownFields?: boolean | undefined | null): Buffer
+/** Precompiled schema reused across encode_with and decode_with calls */
+export declare class SchemaHandle { }
+/** Cheap structural metrics for admission control */
+export interface QuickStats {
+  /** Number of top-level fields */
+  fieldCount: number
+  /** Approximate nesting depth (length-delimited payloads that parse as messages count as nested) */
+  maxDepthApprox: number
+  /** Number of bytes scanned */
+  byteLength: number
+}
+/** A decoded field tag */
+export interface Tag {
+  fieldNumber: number
+  wireType: number
+}
+/** Offsets of a length-delimited payload, relative to the start of the Reader */
+export interface ByteRange {
+  start: number
+  end: number
+}
+/** A map entry decoded by Reader.readMapEntry */
+export interface MapEntry {
+  key: number | boolean | string | Buffer
+  value: number | boolean | string | Buffer
+}
+/** Oneof member matched by Reader.oneof */
+export interface OneofCase {
+  caseFieldNumber: number
+  wireType: number
+}
+/**
+ * A field decoded without a schema
+ * Varints are numbers (or BigInt above 2^53 - 1), fixed32 is a number, fixed64 is a BigInt
+ * and length-delimited values are raw Buffers
+ */
+export interface FieldValue {
+  fieldNumber: number
+  wireType: number
+  value: number | bigint | Buffer
+}
 /** Binary reader for Protocol Buffer messages */
 export declare class Reader {
-  /** Create a new Reader from a Buffer or Uint8Array */
+  /**
+   * Create a new Reader from a Buffer or Uint8Array
+   * The bytes are read in place rather than copied, so writes to the buffer while the
+   * Reader is in use are visible to it
+   */
   constructor(buffer: Buffer)
+  /**
+   * Create a Reader over `length` bytes starting at `offset`
+   * The region is read in place, so one message can be read out of a large archive cheaply.
+   * Wrap an ArrayBuffer with Buffer.from(arrayBuffer), which does not copy it
+   */
+  static fromSlice(buffer: Buffer, offset: number, length: number): Reader
+  /** Enable or disable hex context around the failure position in decode errors */
+  decodeErrorContext(enabled: boolean): this
+  /**
+   * Set the deepest nesting accepted from subReader() and group skipping, counted from the
+   * Reader created from a Buffer (default 64). Deeper input fails with
+   * "Message nesting too deep" (or "Group nesting too deep" for groups)
+   */
+  setMaxDepth(depth: number): this
+  /**
+   * Set the longest length-delimited field accepted by bytes(), string(), bytesRange(),
+   * subReader() and skipType() (default 64MB). The declared length is checked before any
+   * bytes are sliced
+   */
+  setMaxFieldLength(bytes: number): this
+  /**
+   * Reject varints with redundant trailing bytes (e.g. 0x96 0x81 0x00 for 150) in values,
+   * tags and lengths, for input that must be in canonical form. Off by default, matching
+   * protobuf.js
+   */
+  strictVarints(enabled: boolean): this
+  /**
+   * Read a field tag and return its field number and wire type in one call
+   * Unknown wire types and field number 0 are rejected without advancing the position
+   */
+  tag(): Tag
+  /**
+   * Decode the next field tag without advancing, as tag() does
+   * Returns null at the end of the readable region; a truncated or invalid tag is an error
+   */
+  peekTag(): Tag | null
   /** Read uint32 (varint decoded) */
   uint32(): number
   /** Read int32 (varint decoded) */
@@ -30,8 +301,18 @@ export declare class Reader {
   int64(): number
   /** Read sint64 (zigzag decoded) */
   sint64(): number
+  /**
+   * Read a varint as its exact unsigned 64-bit value, with no sign handling
+   * Varints whose 10th byte carries bits beyond 2^64 are rejected as overflowing
+   */
+  rawVarint(): bigint
   /** Read bool (varint decoded) */
   bool(): boolean
+  /**
+   * Read bool, rejecting any value other than 0 or 1
+   * bool() takes every nonzero value as true; this surfaces fields read with the wrong type
+   */
+  boolStrict(): boolean
   /** Read fixed32 (little-endian 4 bytes) */
   fixed32(): number
   /** Read sfixed32 (little-endian 4 bytes) */
@@ -40,27 +321,260 @@ export declare class Reader {
   fixed64(): number
   /** Read sfixed64 (little-endian 8 bytes) */
   sfixed64(): number
+  /** Read uint64 as a BigInt, exact up to 2^64 - 1 */
+  uint64Bigint(): bigint
+  /** Read int64 as a BigInt, exact over the whole signed 64-bit range */
+  int64Bigint(): bigint
+  /** Read sint64 (zigzag decoded) as a BigInt */
+  sint64Bigint(): bigint
+  /** Read fixed64 as a BigInt, exact up to 2^64 - 1 */
+  fixed64Bigint(): bigint
+  /** Read sfixed64 as a BigInt */
+  sfixed64Bigint(): bigint
   /** Read float (32-bit, little-endian) */
   float(): number
   /** Read double (64-bit, little-endian) */
   double(): number
   /** Read bytes (length-delimited) */
   bytes(): Buffer
+  /**
+   * Read a length-delimited field like bytes(), but return where its payload lies instead of
+   * copying it, so large payloads can be sliced out of the original buffer with subarray()
+   */
+  bytesRange(): ByteRange
+  /**
+   * Read a length-delimited field and return a Reader over exactly its payload, moving this
+   * Reader past it. The payload's end is a hard boundary for the new Reader: len(),
+   * remaining() and end-of-buffer errors reflect the nested length, and limit() cannot extend
+   * past it. No bytes are copied
+   */
+  subReader(): Reader
+  /**
+   * Read the next message of a stream of length-prefixed messages, returning its bytes, or
+   * null at the end of the readable region. A length running past the end is an error
+   */
+  readDelimited(): Buffer | null
+  /** Read a fixed-width (2, 4 or 8 byte) length header as used by non-varint framing */
+  fixedLengthPrefix(bytes: number, bigEndian: boolean): number
+  /**
+   * Read a length header whose encoding is chosen by a leading flag byte: 0x00 means a
+   * varint length follows, 0x01 a little-endian fixed32 length. Any other flag is an error
+   * and is left unconsumed
+   */
+  flexibleLength(): number
+  /** Read a single raw byte (no varint decoding) */
+  byte(): number
+  /** Read exactly `length` raw bytes (no length prefix) */
+  readBytesExact(length: number): Buffer
   /** Read string (UTF-8, length-delimited) */
   string(): string
+  /**
+   * Read an enum value (int32 varint) and map it to its name
+   * Unknown values fall back to the numeric string (proto3 open enums) unless `strict` is set,
+   * in which case they throw
+   */
+  enumName(mapping: Record<string, string>, strict?: boolean | undefined | null): string
+  /** Read a NUL-terminated UTF-8 string, consuming the terminator */
+  cString(): string
+  /** Read a packed repeated bool field (length-delimited run of varints) */
+  packedBool(): Array<boolean>
+  /** Read a packed repeated sfixed32 field into an Int32Array */
+  packedSfixed32(): Int32Array
+  /**
+   * Read a packed repeated fixed64 field into a BigInt64Array without precision loss
+   * Values above 2^63 - 1 appear as negative; reinterpret with BigInt.asUintN(64, v)
+   */
+  packedFixed64Big(): BigInt64Array
+  /**
+   * Read a packed repeated sint64 field of deltas and return the running sums
+   * Sums wrap on overflow, matching int64 arithmetic in the producer
+   */
+  packedSint64Cumsum(): BigInt64Array
+  /** Read a packed repeated uint32 field */
+  readPackedUint32(): Array<number>
+  /** Read a packed repeated sint32 field (ZigZag decoded) */
+  readPackedSint32(): Array<number>
+  /** Read a packed repeated int64 field */
+  readPackedInt64(): Array<number>
+  /** Read a packed repeated fixed32 field */
+  readPackedFixed32(): Array<number>
+  /** Read a packed repeated double field */
+  readPackedDouble(): Array<number>
+  /**
+   * Read `count` consecutive little-endian 64-bit values (no tags or length prefix)
+   * into a BigInt64Array
+   */
+  fixed64ArrayBig(count: number): BigInt64Array
+  /**
+   * Scan the rest of the message without decoding values and report cheap aggregate metrics
+   * The position is not advanced
+   */
+  quickStats(): QuickStats
+  /**
+   * Hash the rest of the message field by field, returning a Map keyed by field number
+   * Each digest covers the bytes after the field's tag (including any length prefix); the
+   * occurrences of a repeated field are hashed as one concatenation, in order. `algorithm`
+   * is "sha256" (default) or "fnv1a64". The position is not advanced
+   */
+  fieldHashes(algorithm?: string | undefined | null): Map<number, Buffer>
+  /**
+   * Read a tag and, if its field number is one of the oneof's member fields, return the case
+   * with the value ready to read. Otherwise (or at end of buffer) the position is restored
+   * and null is returned
+   */
+  oneof(caseFields: Uint32Array): OneofCase | null
+  /**
+   * Decode every remaining field without a schema
+   * Repeated fields appear once per occurrence, in wire order
+   */
+  readAll(): Array<FieldValue>
+  /**
+   * Decode fields until `terminator_tag` (a raw tag varint) is read, consuming the terminator
+   * Throws if the buffer ends before the terminator
+   */
+  readUntilTag(terminatorTag: number): Array<FieldValue>
+  /**
+   * Read consecutive unpacked occurrences of `field_number` (tag, value, tag, value, ...)
+   * starting at a tag. Stops and rewinds at the first tag for a different field.
+   * `field_type` is a numeric type name or "bool"; 64-bit values are returned as numbers
+   */
+  repeatedScalar(fieldNumber: number, fieldType: string): Array<number | boolean>
+  /**
+   * Read one length-delimited map entry (field 1 = key, field 2 = value) and decode both
+   * `key_type` is an integer type name, "bool" or "string"; `value_type` is any type name.
+   * Numbers come back as numbers (64-bit ones included), strings as strings, and "bytes" and
+   * "message" values as Buffers to decode separately. A missing key or value is its type's
+   * zero value (0, false, "" or an empty Buffer) and other fields in the entry are skipped
+   */
+  readMapEntry(keyType: string, valueType: string): MapEntry
+  /**
+   * Peek the next tag without consuming it and check whether it is for `field_number`
+   * Returns false at end of buffer
+   */
+  nextFieldNumberIs(fieldNumber: number): boolean
+  /**
+   * Read the fields of a group whose StartGroup tag for `field_number` was just read,
+   * calling `callback(fieldNumber, wireType, value)` for each and consuming the EndGroup tag.
+   * Values are decoded as in readAll; a nested group's value is a Buffer holding its body
+   * and EndGroup tag, so it can be read with a new Reader and readGroupFields. The callback
+   * must not use this Reader; its position and limit are restored after each call
+   */
+  readGroupFields(fieldNumber: number, callback: (...args: any[]) => any): this
+  /**
+   * Parse the remaining bytes as a message of the compiled schema, calling handlers as
+   * each part is reached instead of building an object: `field(name, fieldNumber, value)`
+   * for every non-message value (once per element of a packed field), and
+   * `startMessage(name, fieldNumber)` / `endMessage(name, fieldNumber)` around each nested
+   * message's fields. Any handler may be omitted. Unknown fields are skipped unless the schema
+   * rejects them. Handlers must not use this Reader
+   */
+  parseEvents(schema: SchemaHandle, handlers: object): this
+  /**
+   * Decode the remaining bytes with a compiled schema, as decodeWith does, except that
+   * top-level fields whose number is in `denylist` are skipped without being decoded, so
+   * their values never reach JS. Consumes the rest of the buffer
+   */
+  decodeExcluding(schema: SchemaHandle, denylist: Uint32Array): object
+  /**
+   * Decode the remaining bytes with a compiled schema into element `row` of caller-owned
+   * typed array columns, keyed by field number in `columns`, so a stream of messages can
+   * fill preallocated columns one message at a time. Column types match
+   * decodeBatchColumnar; fields without a column are ignored and absent fields store the
+   * type's default. Consumes the rest of the buffer
+   */
+  collectInto(schema: SchemaHandle, columns: object, row: number): this
   /** Skip a specific number of bytes */
   skip(length: number): this
-  /** Skip a field based on wire type */
+  /**
+   * Skip a field based on wire type
+   * A group is skipped through its matching EndGroup, nested groups included; when the
+   * StartGroup tag was read with tag() the EndGroup must carry the same field number
+   */
   skipType(wireType: number): this
+  /**
+   * Skip the next `count` fields, reading each tag and skipping its value in one call
+   * Groups are skipped whole; fails if the buffer ends before `count` fields are skipped
+   */
+  skipFields(count: number): this
+  /**
+   * Advance to the end of the readable region (the limit, if one is set) regardless of the
+   * current position. For a reader over a nested message this jumps past whatever the
+   * handler left unread
+   */
+  skipToEnd(): this
+  /**
+   * Confine reading to the first `max_pos` bytes; reads past it fail as end of buffer
+   * even though more bytes exist. Replaces any earlier limit
+   */
+  limit(maxPos: number): this
+  /** Remove the limit set with limit(), making the whole buffer readable again */
+  clearLimit(): this
+  /**
+   * Move back to the start of the most recently read field tag (as read by oneof() and the
+   * other tag-reading methods), e.g. to re-dispatch or capture a field whose value failed
+   * validation
+   */
+  rewindField(): this
   /** Get current position */
   pos(): number
+  /** Get current position (file-style alias of pos) */
+  tell(): number
+  /**
+   * Move the position like fseek
+   * `whence` is 0 for the start (default), 1 for the current position, 2 for the end
+   * (the limit, if one is set)
+   */
+  seek(offset: number, whence?: number | undefined | null): this
+  /**
+   * Move back to the start of the buffer to read it again (equivalent to seek(0)); any
+   * limit stays in place
+   */
+  reset(): this
+  /** Length of the readable region in bytes (the limit, if one is set) */
+  len(): number
+  /** Whether the readable region is empty (a limit of 0 counts as empty) */
+  isEmpty(): boolean
+  /** Number of bytes left to read, never negative */
+  remaining(): number
+  /**
+   * Create an independent Reader at the same position, with the same limit and settings,
+   * e.g. to try one interpretation of the following bytes and fall back to another. The
+   * Buffer is shared rather than copied
+   */
+  clone(): Reader
 }
 /** Binary writer for Protocol Buffer messages with fluent API */
 export declare class Writer {
-  /** Create a new Writer */
-  constructor()
+  /**
+   * Create a new Writer, optionally preallocating `capacity` bytes for large messages
+   * Without a capacity nothing is preallocated, so small messages stay in the inline buffer
+   */
+  constructor(capacity?: number | undefined | null)
+  /** Create a Writer that also records every write for text-format rendering */
+  static newWithTrace(): Writer
+  /**
+   * Create a Writer whose finish() checks that the output parses back as protobuf
+   * Unclosed forks and malformed fields make finish() throw instead of returning bad bytes
+   */
+  static newWithVerification(): Writer
+  /**
+   * Create a Writer that streams its output to `on_bytes(chunk)` instead of holding it all
+   * Bytes are flushed once at least `flush_threshold` (default 16 KiB) are buffered outside
+   * any open fork, and the rest by finish(). The callback must not write to this Writer
+   */
+  static newWithSink(onBytes: (...args: any[]) => any, flushThreshold?: number | undefined | null): Writer
+  /**
+   * Write a field tag for `field_number` and `wire_type`
+   * Fails on unknown wire types and field numbers outside 1 to 2^29 - 1
+   */
+  tag(fieldNumber: number, wireType: number): this
   /** Write uint32 (varint encoded) */
   uint32(value: number): this
+  /**
+   * Write uint32 as a varint padded to exactly 5 bytes
+   * The fixed width lets the value be patched later without shifting what follows
+   */
+  uint32Padded(value: number): this
   /** Write int32 (varint encoded) */
   int32(value: number): this
   /** Write sint32 (zigzag + varint encoded) */
@@ -71,8 +585,15 @@ export declare class Writer {
   int64(value: number): this
   /** Write sint64 (zigzag + varint encoded) */
   sint64(value: number): this
+  /**
+   * Write the exact unsigned 64-bit value of a BigInt as a varint, with no sign handling
+   * Negative values and values of 2^64 or more are rejected
+   */
+  rawVarint(value: bigint): this
   /** Write bool (varint encoded as 0 or 1) */
   bool(value: boolean): this
+  /** Write a single raw byte (0-255), e.g. a type tag or marker */
+  byte(value: number): this
   /** Write fixed32 (little-endian 4 bytes) */
   fixed32(value: number): this
   /** Write sfixed32 (little-endian 4 bytes) */
@@ -81,26 +602,211 @@ export declare class Writer {
   fixed64(value: number): this
   /** Write sfixed64 (little-endian 8 bytes) */
   sfixed64(value: number): this
-  /** Write float (32-bit, little-endian) */
+  /** Write uint64 from a BigInt without precision loss (0 to 2^64 - 1) */
+  uint64Bigint(value: bigint): this
+  /** Write int64 from a BigInt without precision loss (-2^63 to 2^63 - 1) */
+  int64Bigint(value: bigint): this
+  /** Write sint64 (zigzag + varint encoded) from a BigInt without precision loss */
+  sint64Bigint(value: bigint): this
+  /** Write fixed64 from a BigInt without precision loss (0 to 2^64 - 1) */
+  fixed64Bigint(value: bigint): this
+  /** Write sfixed64 from a BigInt without precision loss (-2^63 to 2^63 - 1) */
+  sfixed64Bigint(value: bigint): this
+  /**
+   * Write float (32-bit, little-endian)
+   * NaN, the infinities and -0 keep their meaning; finite values beyond the f32 range
+   * become infinities
+   */
   float(value: number): this
   /** Write double (64-bit, little-endian) */
   double(value: number): this
-  /** Write bytes (length-delimited) */
-  bytes(value: Buffer): this
+  /**
+   * Write bytes (length-delimited) from a Buffer or any Uint8Array
+   * The bytes are copied once, straight into the output
+   */
+  bytes(value: Uint8Array): this
   /** Write string (UTF-8, length-delimited) */
   string(value: string): this
+  /**
+   * Write a string field from bytes that are already UTF-8 encoded, skipping the round trip
+   * through a JS string. Invalid UTF-8 is rejected
+   */
+  stringBytes(value: Uint8Array): this
+  /** Write a packed repeated sfixed32 payload (length-delimited, 4 bytes per value) */
+  packedSfixed32(values: Int32Array): this
+  /**
+   * Write a packed repeated uint32 payload (length-delimited varints) from a plain number array
+   * Every element is validated first; the first bad one throws ERR_RANGE naming its index
+   */
+  packedUint32FromArray(values: Array<number>): this
+  /**
+   * Write a packed repeated int32 payload (length-delimited varints) from a plain number array
+   * Negative values take 10 bytes each, as for int32()
+   */
+  packedInt32FromArray(values: Array<number>): this
+  /**
+   * Write a packed repeated sint32 payload (length-delimited zigzag varints) from a plain
+   * number array
+   */
+  packedSint32FromArray(values: Array<number>): this
+  /**
+   * Write a packed repeated double payload (length-delimited, 8 bytes per value) from a plain
+   * number array
+   */
+  packedDoubleFromArray(values: Array<number>): this
+  /**
+   * Write a packed repeated float payload (length-delimited, 4 bytes per value) from a plain
+   * number array. Values are rounded to single precision
+   */
+  packedFloatFromArray(values: Array<number>): this
+  /**
+   * Write a packed repeated uint32 payload (length prefix, then each value as a varint)
+   * An empty array writes a zero length prefix. Validated as packedUint32FromArray()
+   */
+  writePackedUint32(values: Array<number>): this
+  /** Write a packed repeated sint32 payload (length prefix, then zigzag varints) */
+  writePackedSint32(values: Array<number>): this
+  /**
+   * Write a packed repeated int64 payload (length prefix, then varints)
+   * Every element is validated first; the first bad one throws ERR_RANGE naming its index
+   */
+  writePackedInt64(values: Array<number>): this
+  /**
+   * Write a packed repeated fixed32 payload (length prefix, then 4 bytes per value)
+   * Every element is validated first; the first bad one throws ERR_RANGE naming its index
+   */
+  writePackedFixed32(values: Array<number>): this
+  /** Write a packed repeated double payload (length prefix, then 8 bytes per value) */
+  writePackedDouble(values: Array<number>): this
+  /**
+   * Write float values as consecutive little-endian 4-byte values (no tag or length prefix)
+   * The typed array's backing memory is copied in a single memcpy on little-endian hosts;
+   * big-endian hosts convert each element. Wrap in fork/ldelim to emit a packed field
+   */
+  floatArray(values: Float32Array): this
+  /**
+   * Write double values as consecutive little-endian 8-byte values (no tag or length prefix)
+   * Uses the same single-memcpy fast path as float_array
+   */
+  doubleArray(values: Float64Array): this
+  /**
+   * Write fixed32 values as consecutive little-endian 4-byte values (no tag or length prefix)
+   * Uses the same single-memcpy fast path as float_array
+   */
+  fixed32Array(values: Uint32Array): this
+  /**
+   * Write a fresh tag for `new_field_number` followed by pre-encoded value bytes verbatim
+   * For length-delimited fields `value_bytes` must include the length prefix
+   */
+  rewriteField(newFieldNumber: number, wireType: number, valueBytes: Buffer): this
+  /**
+   * Write each pre-encoded message as its own tagged, length-delimited occurrence of
+   * a repeated message field. Nothing is written if the batch would exceed the maximum size
+   */
+  repeatedMessage(fieldNumber: number, messages: Array<Buffer>): this
+  /**
+   * Write the fields of a compiled schema whose presence bit is set, taking each value from
+   * `values` at the field's position in the schema. Bit i of `presenceBits` (byte i / 8,
+   * least significant bit first) marks field i present; present fields are written even
+   * when they hold a default value, as with proto2 has-bits. Nothing is written on error
+   */
+  encodePresent(schema: SchemaHandle, presenceBits: Uint8Array, values: Array<any>): this
+  /**
+   * Write another Writer's contents as a length-delimited message
+   * The body's length is known up front, so no reserved bytes need to be shifted
+   */
+  writeMessageDelimited(body: Writer): this
+  /**
+   * Write an already-encoded message prefixed with its length as a varint32, for framing a
+   * stream of messages (readDelimited() reads them back). An empty message is a single 0x00
+   */
+  writeDelimited(message: Buffer): this
+  /**
+   * Append another Writer's bytes as-is, with no tag or length prefix
+   * Inside an open fork the bytes become part of that fork's content. The other Writer must
+   * have no open forks, since their length prefixes are not filled in yet
+   */
+  merge(other: Writer): this
   /**
    * Fork the writer for nested messages
    * Returns the position for later length calculation
    */
   fork(): number
+  /**
+   * Fork the writer for a frame with a fixed-width (2, 4 or 8 byte) length header
+   * The header is reserved now and backfilled with the body length by ldelim()
+   */
+  forkFixedLength(bytes: number, bigEndian: boolean): number
+  /** Write a fixed-width (2, 4 or 8 byte) length header as used by non-varint framing */
+  fixedLengthPrefix(length: number, bytes: number, bigEndian: boolean): this
   /**
    * Write length delimiter after fork
    * Calculates the length of the nested message and updates the length prefix
+   * Frames opened with fork_fixed_length() get their fixed-width header backfilled instead
    */
   ldelim(): this
-  /** Finish writing and return the buffer */
+  /** Close every open fork, innermost first, as repeated ldelim() calls would */
+  ldelimAll(): this
+  /** Number of forks opened and not yet closed with ldelim() */
+  depth(): number
+  /**
+   * Discard everything written after `position` (a value of len()), e.g. a field that turns
+   * out to be omitted. Fails past the end, inside or before the length prefix of an open
+   * fork, before bytes already flushed to a sink, and on Writers created with new_with_trace
+   */
+  truncate(position: number): this
+  /**
+   * Discard the most recent open fork along with everything written since it, as if
+   * fork() had never been called
+   */
+  rollbackToFork(): this
+  /**
+   * Limit the total encoded size; writes that would exceed it throw ERR_MAX_SIZE
+   * and leave the buffer unchanged. Pass null to remove the limit.
+   * Bytes reserved for the length prefix of an open fork count towards the limit
+   */
+  setMaxSize(maxBytes?: number | undefined | null): this
+  /**
+   * Enable or disable a running CRC-32 that is updated as bytes are appended
+   * Enabling starts the checksum over the bytes already written
+   */
+  trackCrc32(enabled: boolean): this
+  /**
+   * Get the running CRC-32 of everything written so far
+   * Bytes inside an open fork are included once the fork is closed with ldelim
+   */
+  runningCrc32(): number
+  /** Get the number of bytes written so far, including any already flushed to a sink */
+  len(): number
+  /** Whether nothing has been written yet */
+  isEmpty(): boolean
+  /**
+   * Make room for at least `additional` more bytes, so a large write that follows does
+   * not grow the buffer step by step
+   */
+  reserve(additional: number): this
+  /**
+   * Finish writing and return the buffer
+   * Note: This clones the buffer so the Writer keeps its contents and can continue writing.
+   * Use take() instead when the Writer is done with, to avoid the copy.
+   * A Writer created with new_with_sink flushes everything left and returns an empty buffer.
+   * Throws while any fork is open, since its length prefix would be left as reserved zeros
+   */
   finish(): Buffer
+  /**
+   * Finish writing and move the buffer out without copying it, leaving the Writer reset
+   * and empty. Checks the output as finish() does, and leaves the Writer unchanged if
+   * that fails
+   */
+  take(): Buffer
   /** Reset the writer to reuse it */
   reset(): this
+  /**
+   * Render the recorded writes as protobuf text format: `field_number: value` lines, with
+   * forks as nested `field_number { ... }` blocks. Fields are numbered, not named, since a
+   * Writer has no schema; only tags written with tag() (or methods that write their own
+   * tags) are known, and other writes appear as `# kind: value` comments.
+   * Only available on Writers created with new_with_trace
+   */
+  toTextFormat(): string
 }
@@ -310,9 +310,74 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { Reader, Writer, encodeVarint, decodeVarint } = nativeBinding
+const {
+  Reader,
+  Writer,
+  encodeVarint,
+  encodeVarintPadded,
+  varintLength32,
+  varintLength64,
+  decodeVarint,
+  tryDecodeVarint,
+  crc32,
+  bytesEqual,
+  bytesStartsWith,
+  tryParseMessage,
+  repackField,
+  canonicalizeMessage,
+  minimizeVarints,
+  messageHash,
+  diffMessages,
+  transformMessage,
+  thriftEncodeVarint,
+  thriftDecodeVarint,
+  thriftEncodeFieldHeader,
+  thriftDecodeFieldHeader,
+  SchemaHandle,
+  compileSchema,
+  encodeWith,
+  messagesEqual,
+  encodedSize,
+  decodeWith,
+  decodeWithLength,
+  decodeScalarsInto,
+  decodeFlatSmall,
+  decodeBatchColumnar,
+  protoToJson,
+  jsonToProto,
+} = nativeBinding
 
 module.exports.Reader = Reader
 module.exports.Writer = Writer
 module.exports.encodeVarint = encodeVarint
+module.exports.encodeVarintPadded = encodeVarintPadded
+module.exports.varintLength32 = varintLength32
+module.exports.varintLength64 = varintLength64
 module.exports.decodeVarint = decodeVarint
+module.exports.tryDecodeVarint = tryDecodeVarint
+module.exports.crc32 = crc32
+module.exports.bytesEqual = bytesEqual
+module.exports.bytesStartsWith = bytesStartsWith
+module.exports.tryParseMessage = tryParseMessage
+module.exports.repackField = repackField
+module.exports.canonicalizeMessage = canonicalizeMessage
+module.exports.minimizeVarints = minimizeVarints
+module.exports.messageHash = messageHash
+module.exports.diffMessages = diffMessages
+module.exports.transformMessage = transformMessage
+module.exports.thriftEncodeVarint = thriftEncodeVarint
+module.exports.thriftDecodeVarint = thriftDecodeVarint
+module.exports.thriftEncodeFieldHeader = thriftEncodeFieldHeader
+module.exports.thriftDecodeFieldHeader = thriftDecodeFieldHeader
+module.exports.SchemaHandle = SchemaHandle
+module.exports.compileSchema = compileSchema
+module.exports.encodeWith = encodeWith
+module.exports.messagesEqual = messagesEqual
+module.exports.encodedSize = encodedSize
+module.exports.decodeWith = decodeWith
+module.exports.decodeWithLength = decodeWithLength
+module.exports.decodeScalarsInto = decodeScalarsInto
+module.exports.decodeFlatSmall = decodeFlatSmall
+module.exports.decodeBatchColumnar = decodeBatchColumnar
+module.exports.protoToJson = protoToJson
+module.exports.jsonToProto = jsonToProto
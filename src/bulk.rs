@@ -0,0 +1,80 @@
+//! Bulk conversion between fixed-width numeric slices and little-endian wire bytes
+//!
+//! Protobuf fixed-width values are always little-endian. On little-endian hosts a slice
+//! of numbers already has the wire layout, so conversion is a single memcpy; big-endian
+//! hosts fall back to converting each element.
+
+use std::borrow::Cow;
+
+/// Fixed-width numeric types with a little-endian wire representation
+pub trait FixedLe: Copy + Default {
+    /// Encoded width in bytes
+    const SIZE: usize;
+
+    fn put_le(self, out: &mut Vec<u8>);
+    fn from_le_slice(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_fixed_le {
+    ($($ty:ty),*) => {
+        $(
+            impl FixedLe for $ty {
+                const SIZE: usize = std::mem::size_of::<$ty>();
+
+                #[inline]
+                fn put_le(self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                #[inline]
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    let mut raw = [0u8; std::mem::size_of::<$ty>()];
+                    raw.copy_from_slice(&bytes[..Self::SIZE]);
+                    <$ty>::from_le_bytes(raw)
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_le!(i32, u32, i64, u64, f32, f64);
+
+/// View values as little-endian wire bytes
+/// Borrows the slice's memory directly on little-endian hosts
+pub fn to_le_bytes<T: FixedLe>(values: &[T]) -> Cow<'_, [u8]> {
+    if cfg!(target_endian = "little") {
+        // SAFETY: FixedLe is only implemented for plain numeric types with no padding,
+        // and on little-endian hosts their in-memory layout is the wire layout
+        let bytes = unsafe {
+            std::slice::from_raw_parts(values.as_ptr() as *const u8, values.len() * T::SIZE)
+        };
+        Cow::Borrowed(bytes)
+    } else {
+        let mut out = Vec::with_capacity(values.len() * T::SIZE);
+        for &value in values {
+            value.put_le(&mut out);
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// Decode little-endian wire bytes into values
+/// `bytes.len()` must be a multiple of `T::SIZE`
+pub fn from_le_bytes<T: FixedLe>(bytes: &[u8]) -> Vec<T> {
+    let count = bytes.len() / T::SIZE;
+    if cfg!(target_endian = "little") {
+        let mut values = vec![T::default(); count];
+        // SAFETY: the destination holds exactly count * T::SIZE bytes and any bit
+        // pattern is a valid value for the numeric types implementing FixedLe
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                values.as_mut_ptr() as *mut u8,
+                count * T::SIZE,
+            );
+        }
+        values
+    } else {
+        bytes.chunks_exact(T::SIZE).map(T::from_le_slice).collect()
+    }
+}
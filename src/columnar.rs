@@ -0,0 +1,167 @@
+//! Batch decoding of homogeneous messages into one typed array per field
+
+use crate::field_type::FieldType;
+use crate::schema::{decode_message, CompiledField, CompiledSchema, SchemaHandle, Value};
+use napi::bindgen_prelude::*;
+use napi::{Env, JsObject};
+use napi_derive::napi;
+use std::ops::DerefMut;
+
+/// Values of one field across a batch, stored in the typed array's element type
+enum Column {
+    F64(Vec<f64>),
+    F32(Vec<f32>),
+    I32(Vec<i32>),
+    U32(Vec<u32>),
+    I64(Vec<i64>),
+    U64(Vec<u64>),
+    Bool(Vec<u8>),
+}
+
+impl Column {
+    /// Column for a singular numeric or bool field; other fields have no column
+    fn for_field(field: &CompiledField, capacity: usize) -> Option<Column> {
+        if field.repeated {
+            return None;
+        }
+        let column = match field.ty {
+            FieldType::Double => Column::F64(Vec::with_capacity(capacity)),
+            FieldType::Float => Column::F32(Vec::with_capacity(capacity)),
+            FieldType::Int32 | FieldType::Sint32 | FieldType::Sfixed32 | FieldType::Enum => {
+                Column::I32(Vec::with_capacity(capacity))
+            }
+            FieldType::Uint32 | FieldType::Fixed32 => Column::U32(Vec::with_capacity(capacity)),
+            FieldType::Int64 | FieldType::Sint64 | FieldType::Sfixed64 => {
+                Column::I64(Vec::with_capacity(capacity))
+            }
+            FieldType::Uint64 | FieldType::Fixed64 => Column::U64(Vec::with_capacity(capacity)),
+            FieldType::Bool => Column::Bool(Vec::with_capacity(capacity)),
+            FieldType::String | FieldType::Bytes | FieldType::Message => return None,
+        };
+        Some(column)
+    }
+
+    /// Append a decoded value, or the type's default (zero/false) when the field is absent
+    fn push(&mut self, value: Option<&Value>) {
+        let (float, int) = scalar_parts(value);
+        match self {
+            Column::F64(values) => values.push(float),
+            Column::F32(values) => values.push(float as f32),
+            Column::I32(values) => values.push(int as i32),
+            Column::U32(values) => values.push(int as u32),
+            Column::I64(values) => values.push(int),
+            Column::U64(values) => values.push(int as u64),
+            Column::Bool(values) => values.push(int as u8),
+        }
+    }
+
+    fn set_on(self, obj: &mut JsObject, name: &str) -> Result<()> {
+        match self {
+            Column::F64(values) => obj.set(name, Float64Array::new(values)),
+            Column::F32(values) => obj.set(name, Float32Array::new(values)),
+            Column::I32(values) => obj.set(name, Int32Array::new(values)),
+            Column::U32(values) => obj.set(name, Uint32Array::new(values)),
+            Column::I64(values) => obj.set(name, BigInt64Array::new(values)),
+            Column::U64(values) => obj.set(name, BigUint64Array::new(values)),
+            Column::Bool(values) => obj.set(name, Uint8Array::new(values)),
+        }
+    }
+}
+
+/// A numeric or bool value as (float, integer bits), zero for absent values
+fn scalar_parts(value: Option<&Value>) -> (f64, i64) {
+    match value {
+        Some(Value::Float(v)) => (*v, 0),
+        Some(Value::Int(v)) => (0.0, *v),
+        Some(Value::Bool(v)) => (0.0, *v as i64),
+        _ => (0.0, 0),
+    }
+}
+
+/// Write one decoded message into element `row` of caller-owned typed array columns, keyed
+/// by field number in `columns`. Each singular numeric or bool field with a column gets its
+/// value, or the type's default when absent; column types match decodeBatchColumnar
+pub(crate) fn store_row(
+    schema: &CompiledSchema,
+    values: &[Option<Value>],
+    columns: &JsObject,
+    row: u32,
+) -> Result<()> {
+    for (field, value) in schema.fields.iter().zip(values) {
+        if field.repeated {
+            continue;
+        }
+        let key = field.field_number.to_string();
+        let (float, int) = scalar_parts(value.as_ref());
+        match field.ty {
+            FieldType::Double => store::<Float64Array, _>(columns, &key, row, float)?,
+            FieldType::Float => store::<Float32Array, _>(columns, &key, row, float as f32)?,
+            FieldType::Int32 | FieldType::Sint32 | FieldType::Sfixed32 | FieldType::Enum => {
+                store::<Int32Array, _>(columns, &key, row, int as i32)?
+            }
+            FieldType::Uint32 | FieldType::Fixed32 => {
+                store::<Uint32Array, _>(columns, &key, row, int as u32)?
+            }
+            FieldType::Int64 | FieldType::Sint64 | FieldType::Sfixed64 => {
+                store::<BigInt64Array, _>(columns, &key, row, int)?
+            }
+            FieldType::Uint64 | FieldType::Fixed64 => {
+                store::<BigUint64Array, _>(columns, &key, row, int as u64)?
+            }
+            FieldType::Bool => store::<Uint8Array, _>(columns, &key, row, int as u8)?,
+            FieldType::String | FieldType::Bytes | FieldType::Message => {}
+        }
+    }
+    Ok(())
+}
+
+/// Set element `row` of the typed array stored under `key`, if there is one
+fn store<A, T>(columns: &JsObject, key: &str, row: u32, value: T) -> Result<()>
+where
+    A: FromNapiValue + DerefMut<Target = [T]>,
+{
+    let Some(mut column) = columns.get::<_, A>(key)? else {
+        return Ok(());
+    };
+    let len = column.len();
+    let slot = column.get_mut(row as usize).ok_or_else(|| {
+        Error::from_reason(format!(
+            "Row {} is out of range for the column of field {} (length {})",
+            row, key, len
+        ))
+    })?;
+    *slot = value;
+    Ok(())
+}
+
+/// Decode a batch of messages into one typed array per singular numeric or bool field
+/// Element i of each column comes from message i; absent fields contribute the type's
+/// default. 64-bit types use BigInt64Array/BigUint64Array and bools use Uint8Array.
+/// Repeated, string, bytes and message fields are not included
+#[napi]
+pub fn decode_batch_columnar(env: Env, buffers: Vec<Buffer>, handle: &SchemaHandle) -> Result<JsObject> {
+    let schema = &handle.schema;
+    let mut columns: Vec<Option<Column>> = schema
+        .fields
+        .iter()
+        .map(|field| Column::for_field(field, buffers.len()))
+        .collect();
+
+    for (i, buffer) in buffers.iter().enumerate() {
+        let values = decode_message(schema, buffer.as_ref())
+            .map_err(|e| Error::from_reason(format!("Message {}: {}", i, e)))?;
+        for (column, value) in columns.iter_mut().zip(&values) {
+            if let Some(column) = column {
+                column.push(value.as_ref());
+            }
+        }
+    }
+
+    let mut obj = env.create_object()?;
+    for (field, column) in schema.fields.iter().zip(columns) {
+        if let Some(column) = column {
+            column.set_on(&mut obj, &field.name)?;
+        }
+    }
+    Ok(obj)
+}
@@ -0,0 +1,77 @@
+//! Protobuf field types as named in JS schemas and the wire types they encode with
+
+use crate::wire_type::WireType;
+
+/// A protobuf field type (`.proto` type names)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    Enum,
+    String,
+    Bytes,
+    Message,
+}
+
+impl FieldType {
+    pub fn from_name(name: &str) -> Option<FieldType> {
+        match name {
+            "double" => Some(FieldType::Double),
+            "float" => Some(FieldType::Float),
+            "int32" => Some(FieldType::Int32),
+            "int64" => Some(FieldType::Int64),
+            "uint32" => Some(FieldType::Uint32),
+            "uint64" => Some(FieldType::Uint64),
+            "sint32" => Some(FieldType::Sint32),
+            "sint64" => Some(FieldType::Sint64),
+            "fixed32" => Some(FieldType::Fixed32),
+            "fixed64" => Some(FieldType::Fixed64),
+            "sfixed32" => Some(FieldType::Sfixed32),
+            "sfixed64" => Some(FieldType::Sfixed64),
+            "bool" => Some(FieldType::Bool),
+            "enum" => Some(FieldType::Enum),
+            "string" => Some(FieldType::String),
+            "bytes" => Some(FieldType::Bytes),
+            "message" => Some(FieldType::Message),
+            _ => None,
+        }
+    }
+
+    /// Wire type used for a single (unpacked) value of this type
+    pub fn wire_type(self) -> WireType {
+        match self {
+            FieldType::Double | FieldType::Fixed64 | FieldType::Sfixed64 => WireType::Fixed64,
+            FieldType::Float | FieldType::Fixed32 | FieldType::Sfixed32 => WireType::Fixed32,
+            FieldType::String | FieldType::Bytes | FieldType::Message => WireType::LengthDelimited,
+            _ => WireType::Varint,
+        }
+    }
+
+    /// Whether values of this type are numbers or bools (and so may be packed)
+    pub fn is_scalar(self) -> bool {
+        self.wire_type() != WireType::LengthDelimited
+    }
+
+    /// Whether this type may be a map key (integer types, bool and string)
+    pub fn is_map_key(self) -> bool {
+        !matches!(
+            self,
+            FieldType::Double
+                | FieldType::Float
+                | FieldType::Enum
+                | FieldType::Bytes
+                | FieldType::Message
+        )
+    }
+}
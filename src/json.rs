@@ -0,0 +1,535 @@
+//! Conversion between protobuf binary and proto3 JSON using a compiled schema
+//!
+//! Field names are written in lowerCamelCase, 64-bit integers as strings, bytes as base64
+//! and enums by name, following the proto3 JSON mapping. Parsing accepts the same forms plus
+//! original field names, numeric strings for integers, and enum numbers.
+
+use crate::field_type::FieldType;
+use crate::schema::{
+    decode_message, encode_message, CompiledField, CompiledSchema, SchemaHandle, Value,
+};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Decode a buffer with a compiled schema and serialize it as a proto3 JSON string
+/// Only fields present on the wire are written; unknown enum values are written as numbers
+#[napi]
+pub fn proto_to_json(handle: &SchemaHandle, buffer: Buffer) -> Result<String> {
+    let values = decode_message(&handle.schema, buffer.as_ref()).map_err(Error::from_reason)?;
+    let mut out = String::new();
+    write_message(&handle.schema, &values, &mut out);
+    Ok(out)
+}
+
+/// Parse a proto3 JSON string and encode it with a compiled schema
+/// Fields are matched by JSON (lowerCamelCase) or original name and null means absent.
+/// Unknown fields throw unless `ignore_unknown_fields` is set
+#[napi]
+pub fn json_to_proto(
+    handle: &SchemaHandle,
+    json: String,
+    ignore_unknown_fields: Option<bool>,
+) -> Result<Buffer> {
+    let parsed = JsonParser::new(&json).parse_document().map_err(Error::from_reason)?;
+    let values = message_from_json(&handle.schema, &parsed, ignore_unknown_fields.unwrap_or(false))
+        .map_err(Error::from_reason)?;
+    let mut out = Vec::new();
+    encode_message(&handle.schema, &values, &mut out);
+    Ok(Buffer::from(out))
+}
+
+/// lowerCamelCase JSON name for a field: underscores are dropped and the next letter uppercased
+pub fn json_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn write_message(schema: &CompiledSchema, values: &[Option<Value>], out: &mut String) {
+    out.push('{');
+    let mut first = true;
+    for (field, value) in schema.fields.iter().zip(values) {
+        let Some(value) = value else {
+            continue;
+        };
+        if !first {
+            out.push(',');
+        }
+        first = false;
+        write_string(&json_name(&field.name), out);
+        out.push(':');
+        write_value(field, value, out);
+    }
+    out.push('}');
+}
+
+fn write_value(field: &CompiledField, value: &Value, out: &mut String) {
+    match value {
+        Value::Float(v) => write_float(field.ty, *v, out),
+        Value::Int(v) => write_int(field, *v, out),
+        Value::Bool(v) => out.push_str(if *v { "true" } else { "false" }),
+        Value::String(s) => write_string(s, out),
+        Value::Bytes(b) => {
+            out.push('"');
+            out.push_str(&base64_encode(b));
+            out.push('"');
+        }
+        Value::Message(values) => match &field.message {
+            Some(schema) => write_message(schema, values, out),
+            None => out.push_str("{}"),
+        },
+        Value::List(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(field, item, out);
+            }
+            out.push(']');
+        }
+    }
+}
+
+/// Non-finite values use the quoted names the JSON mapping defines
+fn write_float(ty: FieldType, v: f64, out: &mut String) {
+    if v.is_nan() {
+        out.push_str("\"NaN\"");
+    } else if v.is_infinite() {
+        out.push_str(if v > 0.0 { "\"Infinity\"" } else { "\"-Infinity\"" });
+    } else if ty == FieldType::Float {
+        // Print the shortest form that round-trips as f32, not the widened f64
+        out.push_str(&(v as f32).to_string());
+    } else {
+        out.push_str(&v.to_string());
+    }
+}
+
+fn write_int(field: &CompiledField, v: i64, out: &mut String) {
+    match field.ty {
+        FieldType::Uint32 | FieldType::Fixed32 => out.push_str(&(v as u32).to_string()),
+        FieldType::Int32 | FieldType::Sint32 | FieldType::Sfixed32 => {
+            out.push_str(&(v as i32).to_string())
+        }
+        FieldType::Enum => {
+            let number = v as i32;
+            match field.enum_values.iter().find(|(_, n)| *n == number) {
+                Some((name, _)) => write_string(name, out),
+                None => out.push_str(&number.to_string()),
+            }
+        }
+        FieldType::Uint64 | FieldType::Fixed64 => {
+            out.push('"');
+            out.push_str(&(v as u64).to_string());
+            out.push('"');
+        }
+        _ => {
+            out.push('"');
+            out.push_str(&v.to_string());
+            out.push('"');
+        }
+    }
+}
+
+/// Write a JSON string literal, escaping quotes, backslashes and control characters
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Standard base64 with padding
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// A parsed JSON value; numbers keep their source text so 64-bit integers stay exact
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn kind(&self) -> &'static str {
+        match self {
+            Json::Null => "null",
+            Json::Bool(_) => "a bool",
+            Json::Number(_) => "a number",
+            Json::String(_) => "a string",
+            Json::Array(_) => "an array",
+            Json::Object(_) => "an object",
+        }
+    }
+}
+
+/// Minimal recursive-descent JSON parser
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+/// Deepest array/object nesting accepted, so hostile input can't exhaust the stack
+const MAX_JSON_DEPTH: usize = 100;
+
+impl<'a> JsonParser<'a> {
+    fn new(text: &'a str) -> Self {
+        JsonParser { bytes: text.as_bytes(), pos: 0, depth: 0 }
+    }
+
+    fn parse_document(&mut self) -> std::result::Result<Json, String> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos != self.bytes.len() {
+            return Err(self.error("Unexpected trailing characters"));
+        }
+        Ok(value)
+    }
+
+    fn error(&self, reason: &str) -> String {
+        format!("Invalid JSON: {} at offset {}", reason, self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.bytes.get(self.pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect_literal(&mut self, literal: &str, value: Json) -> std::result::Result<Json, String> {
+        if self.bytes[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("Unexpected token"))
+        }
+    }
+
+    fn parse_value(&mut self) -> std::result::Result<Json, String> {
+        self.skip_whitespace();
+        match self.bytes.get(self.pos) {
+            None => Err(self.error("Unexpected end of input")),
+            Some(b'n') => self.expect_literal("null", Json::Null),
+            Some(b't') => self.expect_literal("true", Json::Bool(true)),
+            Some(b'f') => self.expect_literal("false", Json::Bool(false)),
+            Some(b'"') => self.parse_string().map(Json::String),
+            Some(b'[') => self.parse_array(),
+            Some(b'{') => self.parse_object(),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(self.error("Unexpected token")),
+        }
+    }
+
+    fn enter(&mut self) -> std::result::Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_JSON_DEPTH {
+            return Err(self.error("Nesting too deep"));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn parse_array(&mut self) -> std::result::Result<Json, String> {
+        self.enter()?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+            self.depth -= 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b']') => break,
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+        self.pos += 1;
+        self.depth -= 1;
+        Ok(Json::Array(items))
+    }
+
+    fn parse_object(&mut self) -> std::result::Result<Json, String> {
+        self.enter()?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            self.depth -= 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.bytes.get(self.pos) != Some(&b'"') {
+                return Err(self.error("Expected property name"));
+            }
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            if self.bytes.get(self.pos) != Some(&b':') {
+                return Err(self.error("Expected ':'"));
+            }
+            self.pos += 1;
+            members.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => break,
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+        self.pos += 1;
+        self.depth -= 1;
+        Ok(Json::Object(members))
+    }
+
+    fn parse_number(&mut self) -> std::result::Result<Json, String> {
+        let start = self.pos;
+        while matches!(
+            self.bytes.get(self.pos),
+            Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+        ) {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or_default();
+        if text.parse::<f64>().is_err() {
+            return Err(self.error("Invalid number"));
+        }
+        Ok(Json::Number(text.to_string()))
+    }
+
+    fn parse_string(&mut self) -> std::result::Result<String, String> {
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let start = self.pos;
+            while !matches!(self.bytes.get(self.pos), None | Some(b'"' | b'\\')) {
+                self.pos += 1;
+            }
+            // Input came from a &str and we only split at ASCII bytes, so this is valid UTF-8
+            out.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or_default());
+            match self.bytes.get(self.pos) {
+                None => return Err(self.error("Unterminated string")),
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                _ => {}
+            }
+            let escape = self.bytes.get(self.pos + 1).copied();
+            self.pos += 2;
+            match escape {
+                Some(b'"') => out.push('"'),
+                Some(b'\\') => out.push('\\'),
+                Some(b'/') => out.push('/'),
+                Some(b'b') => out.push('\u{08}'),
+                Some(b'f') => out.push('\u{0c}'),
+                Some(b'n') => out.push('\n'),
+                Some(b'r') => out.push('\r'),
+                Some(b't') => out.push('\t'),
+                Some(b'u') => {
+                    let high = self.parse_hex4()?;
+                    let code = if (0xd800..0xdc00).contains(&high)
+                        && self.bytes[self.pos..].starts_with(b"\\u")
+                    {
+                        self.pos += 2;
+                        let low = self.parse_hex4()?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return Err(self.error("Invalid surrogate pair"));
+                        }
+                        0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                    } else {
+                        high
+                    };
+                    out.push(char::from_u32(code).ok_or_else(|| self.error("Invalid \\u escape"))?);
+                }
+                _ => return Err(self.error("Invalid escape")),
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> std::result::Result<u32, String> {
+        let digits = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| self.error("Invalid \\u escape"))?;
+        self.pos += 4;
+        Ok(digits)
+    }
+}
+
+/// Convert a parsed JSON object into positional field values
+fn message_from_json(
+    schema: &CompiledSchema,
+    json: &Json,
+    ignore_unknown: bool,
+) -> std::result::Result<Vec<Option<Value>>, String> {
+    let Json::Object(members) = json else {
+        return Err(format!("Expected an object, got {}", json.kind()));
+    };
+    let mut values: Vec<Option<Value>> = schema.fields.iter().map(|_| None).collect();
+    for (key, member) in members {
+        let index = schema
+            .fields
+            .iter()
+            .position(|f| f.name == *key || json_name(&f.name) == *key);
+        let Some(index) = index else {
+            if ignore_unknown {
+                continue;
+            }
+            return Err(format!("Unknown field \"{}\"", key));
+        };
+        let field = &schema.fields[index];
+        values[index] = match member {
+            Json::Null => None,
+            Json::Array(items) if field.repeated => Some(Value::List(
+                items
+                    .iter()
+                    .map(|item| value_from_json(field, item, ignore_unknown))
+                    .collect::<std::result::Result<_, _>>()?,
+            )),
+            _ if field.repeated => {
+                return Err(format!("Field {} expects an array, got {}", field.name, member.kind()));
+            }
+            _ => Some(value_from_json(field, member, ignore_unknown)?),
+        };
+    }
+    Ok(values)
+}
+
+fn value_from_json(
+    field: &CompiledField,
+    json: &Json,
+    ignore_unknown: bool,
+) -> std::result::Result<Value, String> {
+    let mismatch = || format!("Field {} cannot be {}", field.name, json.kind());
+    match (field.ty, json) {
+        (FieldType::Message, _) => match &field.message {
+            Some(schema) => message_from_json(schema, json, ignore_unknown).map(Value::Message),
+            None => Err(format!("Message field {} has no schema", field.name)),
+        },
+        (FieldType::String, Json::String(s)) => Ok(Value::String(s.clone())),
+        (FieldType::Bytes, Json::String(s)) => base64_decode(s)
+            .map(Value::Bytes)
+            .ok_or_else(|| format!("Field {} is not valid base64", field.name)),
+        (FieldType::Bool, Json::Bool(b)) => Ok(Value::Bool(*b)),
+        (FieldType::Double | FieldType::Float, Json::Number(text)) => {
+            Ok(Value::Float(text.parse().map_err(|_| mismatch())?))
+        }
+        (FieldType::Double | FieldType::Float, Json::String(s)) => match s.as_str() {
+            "NaN" => Ok(Value::Float(f64::NAN)),
+            "Infinity" => Ok(Value::Float(f64::INFINITY)),
+            "-Infinity" => Ok(Value::Float(f64::NEG_INFINITY)),
+            _ => s.parse().map(Value::Float).map_err(|_| mismatch()),
+        },
+        (FieldType::Enum, Json::String(s)) => match field.enum_values.iter().find(|(name, _)| name == s) {
+            Some(&(_, number)) => Ok(Value::Int(number as i64)),
+            None => Err(format!("Unknown enum value \"{}\" for {}", s, field.name)),
+        },
+        (_, Json::Number(text) | Json::String(text)) if is_integer_type(field.ty) => {
+            integer_from_text(field.ty, text)
+                .map(Value::Int)
+                .ok_or_else(|| format!("Field {} value {} is out of range", field.name, text))
+        }
+        _ => Err(mismatch()),
+    }
+}
+
+fn is_integer_type(ty: FieldType) -> bool {
+    ty.is_scalar() && !matches!(ty, FieldType::Double | FieldType::Float | FieldType::Bool)
+}
+
+/// Parse an integer written as a JSON number or numeric string and check it fits the type
+/// Exponent forms such as 1e3 are accepted when they denote an exact integer
+fn integer_from_text(ty: FieldType, text: &str) -> Option<i64> {
+    let (min, max): (i128, i128) = match ty {
+        FieldType::Uint32 | FieldType::Fixed32 => (0, u32::MAX as i128),
+        FieldType::Uint64 | FieldType::Fixed64 => (0, u64::MAX as i128),
+        FieldType::Int64 | FieldType::Sint64 | FieldType::Sfixed64 => {
+            (i64::MIN as i128, i64::MAX as i128)
+        }
+        _ => (i32::MIN as i128, i32::MAX as i128),
+    };
+    let value = match text.parse::<i128>() {
+        Ok(value) => value,
+        Err(_) => {
+            let float: f64 = text.parse().ok()?;
+            if float.fract() != 0.0 || float.abs() > 1e20 {
+                return None;
+            }
+            float as i128
+        }
+    };
+    (min..=max).contains(&value).then_some(value as i64)
+}
+
+/// Decode standard or URL-safe base64, with or without padding
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut bits: u32 = 0;
+    let mut count = 0;
+    for c in text.bytes() {
+        let sextet = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        bits = (bits << 6) | sextet as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            out.push((bits >> count) as u8);
+        }
+    }
+    // A single leftover character can't encode a whole byte
+    (count < 6).then_some(out)
+}
@@ -1,7 +1,18 @@
+mod bulk;
 mod varint;
 mod wire_type;
+mod field_type;
 mod reader;
 mod writer;
+mod scan;
+mod transform;
+mod trace;
+mod thrift;
+mod hash;
+mod schema;
+mod columnar;
+mod sink;
+mod json;
 
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
@@ -21,6 +32,62 @@ pub fn encode_varint(value: i64) -> Result<Buffer> {
     Ok(Buffer::from(bytes))
 }
 
+/// Encode a uint32 as a varint padded to exactly 5 bytes
+/// Useful as a fixed-width placeholder that can be backfilled in place
+#[napi]
+pub fn encode_varint_padded(value: u32) -> Buffer {
+    Buffer::from(varint::encode_varint32_padded(value).to_vec())
+}
+
+/// Number of bytes encodeVarint would produce for a uint32, computed without encoding
+#[napi]
+pub fn varint_length32(value: u32) -> u32 {
+    varint::varint_len(value as u64) as u32
+}
+
+/// Number of bytes a 64-bit varint occupies, treating the value's bits as unsigned
+/// (negative values take 10 bytes), computed without encoding
+#[napi]
+pub fn varint_length64(value: i64) -> u32 {
+    varint::varint_len(value as u64) as u32
+}
+
+/// Compute the CRC-32 (IEEE) checksum of a buffer
+#[napi]
+pub fn crc32(buffer: Buffer) -> u32 {
+    hash::crc32(buffer.as_ref())
+}
+
+/// Compare two buffers byte for byte
+/// With `constant_time` the time taken depends only on the lengths, not on where the first
+/// difference is, for comparing secrets such as MACs or tokens
+#[napi]
+pub fn bytes_equal(a: Buffer, b: Buffer, constant_time: Option<bool>) -> bool {
+    if !constant_time.unwrap_or(false) {
+        return a[..] == b[..];
+    }
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Check whether a buffer begins with the bytes of `prefix`
+#[napi]
+pub fn bytes_starts_with(buffer: Buffer, prefix: Buffer) -> bool {
+    buffer.starts_with(&prefix)
+}
+
+/// Check whether the bytes from `offset` form a complete, structurally valid message
+/// Returns the message length, or null if the buffer ends mid-field and more data is needed.
+/// Throws if the bytes can never form a valid message
+#[napi]
+pub fn try_parse_message(buffer: Buffer, offset: u32) -> Result<Option<u32>> {
+    let length = scan::message_length(buffer.as_ref(), offset as usize)
+        .map_err(Error::from_reason)?;
+    Ok(length.map(|len| len as u32))
+}
+
 /// Decode a varint from a buffer at the given position
 /// Returns an object with { value, length }
 #[napi(object)]
@@ -36,7 +103,7 @@ pub fn decode_varint(buffer: Buffer, pos: u32) -> Result<VarintResult> {
     let start_offset = offset;
     
     let value = varint::decode_varint64(buf, &mut offset)
-        .map_err(|e| Error::from_reason(e))?;
+        .map_err(Error::from_reason)?;
     
     let length = (offset - start_offset) as u32;
     
@@ -45,3 +112,15 @@ pub fn decode_varint(buffer: Buffer, pos: u32) -> Result<VarintResult> {
         length,
     })
 }
+
+/// Decode a varint from a stream buffer at the given position
+/// Returns null if the buffer ends part-way through the varint (ERR_NEED_MORE_DATA) and
+/// throws ERR_VARINT_OVERFLOW if the varint is longer than 10 bytes or overflows 64 bits
+#[napi]
+pub fn try_decode_varint(buffer: Buffer, pos: u32) -> Result<Option<VarintResult>> {
+    match decode_varint(buffer, pos) {
+        Ok(result) => Ok(Some(result)),
+        Err(e) if e.reason == varint::ERR_NEED_MORE_DATA => Ok(None),
+        Err(e) => Err(e),
+    }
+}
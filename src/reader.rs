@@ -1,221 +1,919 @@
-use crate::varint::{decode_varint32, decode_varint64, zigzag_decode32, zigzag_decode64};
-use crate::wire_type::WireType;
+use crate::bulk::{from_le_bytes, FixedLe};
+use crate::columnar::store_row;
+use crate::field_type::FieldType;
+use crate::hash::HashAlgorithm;
+use crate::scan::{approx_depth, scan_field, scan_fields, scan_group, FieldSpan};
+use crate::schema::{
+    decode_message, decode_message_excluding, message_to_js, span_values, value_to_js, CompiledField,
+    CompiledSchema, SchemaHandle,
+};
+use crate::varint::{
+    decode_varint32, decode_varint64, zigzag_decode32, zigzag_decode64, ERR_NEED_MORE_DATA,
+};
+use crate::wire_type::{decode_tag, WireType};
 use napi::bindgen_prelude::*;
+use napi::{Env, JsFunction, JsObject, JsUnknown, NapiValue};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::rc::Rc;
 use napi_derive::napi;
 
+/// Number of bytes on each side of the failure position included in error context
+const ERROR_CONTEXT_BYTES: usize = 16;
+
+/// Default limit on sub-reader and group nesting
+const DEFAULT_MAX_DEPTH: u32 = 64;
+/// Default limit on the length of a single length-delimited field (64MB)
+const DEFAULT_MAX_FIELD_LENGTH: usize = 64 << 20;
+
+/// Seek relative to the start of the buffer
+const SEEK_SET: u32 = 0;
+/// Seek relative to the current position
+const SEEK_CUR: u32 = 1;
+/// Seek relative to the end of the buffer
+const SEEK_END: u32 = 2;
+
+/// Cheap structural metrics for admission control
+#[napi(object)]
+pub struct QuickStats {
+    /// Number of top-level fields
+    pub field_count: u32,
+    /// Approximate nesting depth (length-delimited payloads that parse as messages count as nested)
+    pub max_depth_approx: u32,
+    /// Number of bytes scanned
+    pub byte_length: u32,
+}
+
+/// A decoded field tag
+#[napi(object)]
+pub struct Tag {
+    pub field_number: u32,
+    pub wire_type: u32,
+}
+
+/// Offsets of a length-delimited payload, relative to the start of the Reader
+#[napi(object)]
+pub struct ByteRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// A key or value decoded by Reader.readMapEntry
+type MapValue = Either4<f64, bool, String, Buffer>;
+
+/// A map entry decoded by Reader.readMapEntry
+#[napi(object)]
+pub struct MapEntry {
+    pub key: MapValue,
+    pub value: MapValue,
+}
+
+/// Oneof member matched by Reader.oneof
+#[napi(object)]
+pub struct OneofCase {
+    pub case_field_number: u32,
+    pub wire_type: u32,
+}
+
+/// Largest integer a JS number represents exactly (2^53 - 1)
+pub(crate) const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// A field decoded without a schema
+/// Varints are numbers (or BigInt above 2^53 - 1), fixed32 is a number, fixed64 is a BigInt
+/// and length-delimited values are raw Buffers
+#[napi(object)]
+pub struct FieldValue {
+    pub field_number: u32,
+    pub wire_type: u32,
+    pub value: Either3<f64, BigInt, Buffer>,
+}
+
+/// The bytes a Reader reads: a window onto the caller's Buffer
+/// Holding the Buffer keeps the JS memory alive, so construction copies nothing. Readers
+/// created with subReader() or clone() share their parent's Buffer
+#[derive(Clone)]
+struct ReadBuffer {
+    data: Rc<Buffer>,
+    start: usize,
+    end: usize,      // Readable end, moved by limit()
+    full_end: usize, // End of the region the Reader was created over
+}
+
+impl ReadBuffer {
+    fn new(data: Rc<Buffer>, start: usize, end: usize) -> Self {
+        ReadBuffer { data, start, end, full_end: end }
+    }
+
+    /// A window onto `range` of this one, sharing the same Buffer
+    fn sub(&self, range: Range<usize>) -> Self {
+        ReadBuffer::new(self.data.clone(), self.start + range.start, self.start + range.end)
+    }
+
+    /// Length of the whole region, ignoring any limit
+    fn full_len(&self) -> usize {
+        self.full_end - self.start
+    }
+
+    /// Make only the first `len` bytes readable, or the whole region for None
+    fn set_limit(&mut self, len: Option<usize>) {
+        self.end = len.map_or(self.full_end, |len| self.start + len);
+    }
+}
+
+impl std::ops::Deref for ReadBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data[self.start..self.end]
+    }
+}
+
 /// Binary reader for Protocol Buffer messages
 #[napi]
 pub struct Reader {
-    buffer: Vec<u8>,
+    buffer: ReadBuffer,
     pos: usize,
+    error_context: bool,
+    depth: u32, // Nesting below the Reader created from a Buffer; subReader() adds 1
+    max_depth: u32, // Deepest sub-reader or group accepted
+    max_field_length: usize, // Longest length-delimited field accepted
+    strict_varints: bool, // Reject varints with redundant trailing bytes
+    last_tag_pos: Option<usize>, // Offset of the most recently read field tag, for rewind_field()
 }
 
 #[napi]
 impl Reader {
     /// Create a new Reader from a Buffer or Uint8Array
+    /// The bytes are read in place rather than copied, so writes to the buffer while the
+    /// Reader is in use are visible to it
     #[napi(constructor)]
     pub fn new(buffer: Buffer) -> Self {
+        let len = buffer.len();
         Reader {
-            buffer: buffer.to_vec(),
+            buffer: ReadBuffer::new(Rc::new(buffer), 0, len),
             pos: 0,
+            error_context: false,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_field_length: DEFAULT_MAX_FIELD_LENGTH,
+            strict_varints: false,
+            last_tag_pos: None,
         }
     }
 
+    /// Create a Reader over `length` bytes starting at `offset`
+    /// The region is read in place, so one message can be read out of a large archive cheaply.
+    /// Wrap an ArrayBuffer with Buffer.from(arrayBuffer), which does not copy it
+    #[napi(factory)]
+    pub fn from_slice(buffer: Buffer, offset: u32, length: u32) -> Result<Self> {
+        let start = offset as usize;
+        let end = start + length as usize;
+        if end > buffer.len() {
+            return Err(Error::from_reason(format!(
+                "Slice out of bounds: offset {} + length {} exceeds buffer length {}",
+                offset,
+                length,
+                buffer.len()
+            )));
+        }
+        Ok(Reader {
+            buffer: ReadBuffer::new(Rc::new(buffer), start, end),
+            pos: 0,
+            error_context: false,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_field_length: DEFAULT_MAX_FIELD_LENGTH,
+            strict_varints: false,
+            last_tag_pos: None,
+        })
+    }
+
+    /// Enable or disable hex context around the failure position in decode errors
+    #[napi]
+    pub fn decode_error_context(&mut self, enabled: bool) -> &Self {
+        self.error_context = enabled;
+        self
+    }
+
+    /// Set the deepest nesting accepted from subReader() and group skipping, counted from the
+    /// Reader created from a Buffer (default 64). Deeper input fails with
+    /// "Message nesting too deep" (or "Group nesting too deep" for groups)
+    #[napi]
+    pub fn set_max_depth(&mut self, depth: u32) -> &Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Set the longest length-delimited field accepted by bytes(), string(), bytesRange(),
+    /// subReader() and skipType() (default 64MB). The declared length is checked before any
+    /// bytes are sliced
+    #[napi]
+    pub fn set_max_field_length(&mut self, bytes: u32) -> &Self {
+        self.max_field_length = bytes as usize;
+        self
+    }
+
+    /// Reject varints with redundant trailing bytes (e.g. 0x96 0x81 0x00 for 150) in values,
+    /// tags and lengths, for input that must be in canonical form. Off by default, matching
+    /// protobuf.js
+    #[napi]
+    pub fn strict_varints(&mut self, enabled: bool) -> &Self {
+        self.strict_varints = enabled;
+        self
+    }
+
+    /// Read a field tag and return its field number and wire type in one call
+    /// Unknown wire types and field number 0 are rejected without advancing the position
+    #[napi]
+    pub fn tag(&mut self) -> Result<Tag> {
+        let (field_number, wire_type) = self.read_tag()?;
+        Ok(Tag {
+            field_number,
+            wire_type: wire_type as u32,
+        })
+    }
+
+    /// Decode the next field tag without advancing, as tag() does
+    /// Returns null at the end of the readable region; a truncated or invalid tag is an error
+    #[napi]
+    pub fn peek_tag(&mut self) -> Result<Option<Tag>> {
+        if self.pos >= self.buffer.len() {
+            return Ok(None);
+        }
+        let (pos, last_tag_pos) = (self.pos, self.last_tag_pos);
+        let tag = self.tag();
+        self.pos = pos;
+        self.last_tag_pos = last_tag_pos;
+        tag.map(Some)
+    }
+
     /// Read uint32 (varint decoded)
     #[napi]
     pub fn uint32(&mut self) -> Result<u32> {
-        decode_varint32(&self.buffer, &mut self.pos)
-            .map_err(|e| Error::from_reason(e))
+        self.varint("uint32", decode_varint32)
     }
 
     /// Read int32 (varint decoded)
     #[napi]
     pub fn int32(&mut self) -> Result<i32> {
         // For negative numbers, this will be encoded as 10 bytes
-        let val = decode_varint64(&self.buffer, &mut self.pos)
-            .map_err(|e| Error::from_reason(e))?;
+        let val = self.varint("int32", decode_varint64)?;
         Ok(val as i32)
     }
 
     /// Read sint32 (zigzag decoded)
     #[napi]
     pub fn sint32(&mut self) -> Result<i32> {
-        let val = decode_varint32(&self.buffer, &mut self.pos)
-            .map_err(|e| Error::from_reason(e))?;
+        let val = self.varint("sint32", decode_varint32)?;
         Ok(zigzag_decode32(val))
     }
 
     /// Read uint64 (varint decoded)
     #[napi]
     pub fn uint64(&mut self) -> Result<i64> {
-        let val = decode_varint64(&self.buffer, &mut self.pos)
-            .map_err(|e| Error::from_reason(e))?;
+        let val = self.varint("uint64", decode_varint64)?;
         Ok(val as i64)
     }
 
     /// Read int64 (varint decoded)
     #[napi]
     pub fn int64(&mut self) -> Result<i64> {
-        let val = decode_varint64(&self.buffer, &mut self.pos)
-            .map_err(|e| Error::from_reason(e))?;
+        let val = self.varint("int64", decode_varint64)?;
         Ok(val as i64)
     }
 
     /// Read sint64 (zigzag decoded)
     #[napi]
     pub fn sint64(&mut self) -> Result<i64> {
-        let val = decode_varint64(&self.buffer, &mut self.pos)
-            .map_err(|e| Error::from_reason(e))?;
+        let val = self.varint("sint64", decode_varint64)?;
         Ok(zigzag_decode64(val))
     }
 
+    /// Read a varint as its exact unsigned 64-bit value, with no sign handling
+    /// Varints whose 10th byte carries bits beyond 2^64 are rejected as overflowing
+    #[napi]
+    pub fn raw_varint(&mut self) -> Result<BigInt> {
+        let val = self.varint("rawVarint", decode_varint64)?;
+        Ok(BigInt::from(val))
+    }
+
     /// Read bool (varint decoded)
     #[napi]
     pub fn bool(&mut self) -> Result<bool> {
-        let val = decode_varint32(&self.buffer, &mut self.pos)
-            .map_err(|e| Error::from_reason(e))?;
+        let val = self.varint("bool", decode_varint32)?;
         Ok(val != 0)
     }
 
+    /// Read bool, rejecting any value other than 0 or 1
+    /// bool() takes every nonzero value as true; this surfaces fields read with the wrong type
+    #[napi]
+    pub fn bool_strict(&mut self) -> Result<bool> {
+        let start = self.pos;
+        let val = self.varint("boolStrict", decode_varint64)?;
+        if val > 1 {
+            self.pos = start;
+            let reason = format!("Invalid boolean value {}", val);
+            return Err(self.error_reading(&reason, "boolStrict"));
+        }
+        Ok(val == 1)
+    }
+
     /// Read fixed32 (little-endian 4 bytes)
     #[napi]
     pub fn fixed32(&mut self) -> Result<u32> {
-        if self.pos + 4 > self.buffer.len() {
-            return Err(Error::from_reason("Unexpected end of buffer"));
-        }
-        let val = u32::from_le_bytes([
-            self.buffer[self.pos],
-            self.buffer[self.pos + 1],
-            self.buffer[self.pos + 2],
-            self.buffer[self.pos + 3],
-        ]);
-        self.pos += 4;
-        Ok(val)
+        self.fixed::<u32>("fixed32")
     }
 
     /// Read sfixed32 (little-endian 4 bytes)
     #[napi]
     pub fn sfixed32(&mut self) -> Result<i32> {
-        if self.pos + 4 > self.buffer.len() {
-            return Err(Error::from_reason("Unexpected end of buffer"));
-        }
-        let val = i32::from_le_bytes([
-            self.buffer[self.pos],
-            self.buffer[self.pos + 1],
-            self.buffer[self.pos + 2],
-            self.buffer[self.pos + 3],
-        ]);
-        self.pos += 4;
-        Ok(val)
+        self.fixed::<i32>("sfixed32")
     }
 
     /// Read fixed64 (little-endian 8 bytes)
     #[napi]
     pub fn fixed64(&mut self) -> Result<i64> {
-        if self.pos + 8 > self.buffer.len() {
-            return Err(Error::from_reason("Unexpected end of buffer"));
-        }
-        let val = u64::from_le_bytes([
-            self.buffer[self.pos],
-            self.buffer[self.pos + 1],
-            self.buffer[self.pos + 2],
-            self.buffer[self.pos + 3],
-            self.buffer[self.pos + 4],
-            self.buffer[self.pos + 5],
-            self.buffer[self.pos + 6],
-            self.buffer[self.pos + 7],
-        ]);
-        self.pos += 8;
-        Ok(val as i64)
+        Ok(self.fixed::<u64>("fixed64")? as i64)
     }
 
     /// Read sfixed64 (little-endian 8 bytes)
     #[napi]
     pub fn sfixed64(&mut self) -> Result<i64> {
-        if self.pos + 8 > self.buffer.len() {
-            return Err(Error::from_reason("Unexpected end of buffer"));
-        }
-        let val = i64::from_le_bytes([
-            self.buffer[self.pos],
-            self.buffer[self.pos + 1],
-            self.buffer[self.pos + 2],
-            self.buffer[self.pos + 3],
-            self.buffer[self.pos + 4],
-            self.buffer[self.pos + 5],
-            self.buffer[self.pos + 6],
-            self.buffer[self.pos + 7],
-        ]);
-        self.pos += 8;
-        Ok(val)
+        self.fixed::<i64>("sfixed64")
+    }
+
+    /// Read uint64 as a BigInt, exact up to 2^64 - 1
+    #[napi]
+    pub fn uint64_bigint(&mut self) -> Result<BigInt> {
+        let val = self.varint("uint64Bigint", decode_varint64)?;
+        Ok(BigInt::from(val))
+    }
+
+    /// Read int64 as a BigInt, exact over the whole signed 64-bit range
+    #[napi]
+    pub fn int64_bigint(&mut self) -> Result<BigInt> {
+        let val = self.varint("int64Bigint", decode_varint64)?;
+        Ok(BigInt::from(val as i64))
+    }
+
+    /// Read sint64 (zigzag decoded) as a BigInt
+    #[napi]
+    pub fn sint64_bigint(&mut self) -> Result<BigInt> {
+        let val = self.varint("sint64Bigint", decode_varint64)?;
+        Ok(BigInt::from(zigzag_decode64(val)))
+    }
+
+    /// Read fixed64 as a BigInt, exact up to 2^64 - 1
+    #[napi]
+    pub fn fixed64_bigint(&mut self) -> Result<BigInt> {
+        Ok(BigInt::from(self.fixed::<u64>("fixed64Bigint")?))
+    }
+
+    /// Read sfixed64 as a BigInt
+    #[napi]
+    pub fn sfixed64_bigint(&mut self) -> Result<BigInt> {
+        Ok(BigInt::from(self.fixed::<i64>("sfixed64Bigint")?))
     }
 
     /// Read float (32-bit, little-endian)
     #[napi]
     pub fn float(&mut self) -> Result<f64> {
-        if self.pos + 4 > self.buffer.len() {
-            return Err(Error::from_reason("Unexpected end of buffer"));
-        }
-        let val = f32::from_le_bytes([
-            self.buffer[self.pos],
-            self.buffer[self.pos + 1],
-            self.buffer[self.pos + 2],
-            self.buffer[self.pos + 3],
-        ]);
-        self.pos += 4;
-        Ok(val as f64)
+        Ok(self.fixed::<f32>("float")? as f64)
     }
 
     /// Read double (64-bit, little-endian)
     #[napi]
     pub fn double(&mut self) -> Result<f64> {
-        if self.pos + 8 > self.buffer.len() {
-            return Err(Error::from_reason("Unexpected end of buffer"));
-        }
-        let val = f64::from_le_bytes([
-            self.buffer[self.pos],
-            self.buffer[self.pos + 1],
-            self.buffer[self.pos + 2],
-            self.buffer[self.pos + 3],
-            self.buffer[self.pos + 4],
-            self.buffer[self.pos + 5],
-            self.buffer[self.pos + 6],
-            self.buffer[self.pos + 7],
-        ]);
-        self.pos += 8;
-        Ok(val)
+        self.fixed::<f64>("double")
     }
 
     /// Read bytes (length-delimited)
     #[napi]
     pub fn bytes(&mut self) -> Result<Buffer> {
-        let len = decode_varint32(&self.buffer, &mut self.pos)
-            .map_err(|e| Error::from_reason(e))? as usize;
-        
+        let range = self.length_delimited("bytes")?;
+        Ok(Buffer::from(self.buffer[range].to_vec()))
+    }
+
+    /// Read a length-delimited field like bytes(), but return where its payload lies instead of
+    /// copying it, so large payloads can be sliced out of the original buffer with subarray()
+    #[napi]
+    pub fn bytes_range(&mut self) -> Result<ByteRange> {
+        let range = self.length_delimited("bytesRange")?;
+        Ok(ByteRange {
+            start: range.start as u32,
+            end: range.end as u32,
+        })
+    }
+
+    /// Read a length-delimited field and return a Reader over exactly its payload, moving this
+    /// Reader past it. The payload's end is a hard boundary for the new Reader: len(),
+    /// remaining() and end-of-buffer errors reflect the nested length, and limit() cannot extend
+    /// past it. No bytes are copied
+    #[napi]
+    pub fn sub_reader(&mut self) -> Result<Reader> {
+        if self.depth >= self.max_depth {
+            return Err(self.error_reading("Message nesting too deep", "subReader"));
+        }
+        let range = self.length_delimited("subReader")?;
+        Ok(Reader {
+            buffer: self.buffer.sub(range),
+            pos: 0,
+            error_context: self.error_context,
+            depth: self.depth + 1,
+            max_depth: self.max_depth,
+            max_field_length: self.max_field_length,
+            strict_varints: self.strict_varints,
+            last_tag_pos: None,
+        })
+    }
+
+    /// Read the next message of a stream of length-prefixed messages, returning its bytes, or
+    /// null at the end of the readable region. A length running past the end is an error
+    #[napi]
+    pub fn read_delimited(&mut self) -> Result<Option<Buffer>> {
+        if self.pos >= self.buffer.len() {
+            return Ok(None);
+        }
+        let range = self.length_delimited("readDelimited")?;
+        Ok(Some(Buffer::from(self.buffer[range].to_vec())))
+    }
+
+    /// Read a fixed-width (2, 4 or 8 byte) length header as used by non-varint framing
+    #[napi]
+    pub fn fixed_length_prefix(&mut self, bytes: u32, big_endian: bool) -> Result<f64> {
+        let width = bytes as usize;
+        if !matches!(width, 2 | 4 | 8) {
+            return Err(self.error_reading(
+                "Length prefix width must be 2, 4 or 8 bytes",
+                "fixedLengthPrefix",
+            ));
+        }
+        if self.pos + width > self.buffer.len() {
+            return Err(self.error_reading("Unexpected end of buffer", "fixedLengthPrefix"));
+        }
+
+        let mut raw = [0u8; 8];
+        let header = &self.buffer[self.pos..self.pos + width];
+        let length = if big_endian {
+            raw[8 - width..].copy_from_slice(header);
+            u64::from_be_bytes(raw)
+        } else {
+            raw[..width].copy_from_slice(header);
+            u64::from_le_bytes(raw)
+        };
+        if length > MAX_SAFE_INTEGER {
+            return Err(self.error_reading("Length prefix exceeds 2^53 - 1", "fixedLengthPrefix"));
+        }
+
+        self.pos += width;
+        Ok(length as f64)
+    }
+
+    /// Read a length header whose encoding is chosen by a leading flag byte: 0x00 means a
+    /// varint length follows, 0x01 a little-endian fixed32 length. Any other flag is an error
+    /// and is left unconsumed
+    #[napi]
+    pub fn flexible_length(&mut self) -> Result<u32> {
+        let start = self.pos;
+        let flag = *self
+            .buffer
+            .get(self.pos)
+            .ok_or_else(|| self.error_reading("Unexpected end of buffer", "flexibleLength"))?;
+        if flag > 1 {
+            let reason = format!("Invalid length flag byte 0x{:02x}", flag);
+            return Err(self.error_reading(&reason, "flexibleLength"));
+        }
+        self.pos += 1;
+        let length = if flag == 0 {
+            self.varint("flexibleLength", decode_varint32)
+        } else {
+            self.fixed::<u32>("flexibleLength")
+        };
+        self.rewind_on_error(start, length)
+    }
+
+    /// Read a single raw byte (no varint decoding)
+    #[napi]
+    pub fn byte(&mut self) -> Result<u32> {
+        let value = *self
+            .buffer
+            .get(self.pos)
+            .ok_or_else(|| self.error_reading("Unexpected end of buffer", "byte"))?;
+        self.pos += 1;
+        Ok(value as u32)
+    }
+
+    /// Read exactly `length` raw bytes (no length prefix)
+    #[napi]
+    pub fn read_bytes_exact(&mut self, length: u32) -> Result<Buffer> {
+        let len = length as usize;
         if self.pos + len > self.buffer.len() {
-            return Err(Error::from_reason("Unexpected end of buffer"));
+            return Err(self.error_reading("Unexpected end of buffer", "readBytesExact"));
         }
-        
+
         let bytes = self.buffer[self.pos..self.pos + len].to_vec();
         self.pos += len;
-        
+
         Ok(Buffer::from(bytes))
     }
 
     /// Read string (UTF-8, length-delimited)
     #[napi]
     pub fn string(&mut self) -> Result<String> {
-        let len = decode_varint32(&self.buffer, &mut self.pos)
-            .map_err(|e| Error::from_reason(e))? as usize;
-        
-        if self.pos + len > self.buffer.len() {
-            return Err(Error::from_reason("Unexpected end of buffer"));
+        let start = self.pos;
+        let range = self.length_delimited("string")?;
+        String::from_utf8(self.buffer[range].to_vec()).map_err(|_| {
+            self.pos = start;
+            self.error_reading("Invalid UTF-8 string", "string")
+        })
+    }
+
+    /// Read an enum value (int32 varint) and map it to its name
+    /// Unknown values fall back to the numeric string (proto3 open enums) unless `strict` is set,
+    /// in which case they throw
+    #[napi]
+    pub fn enum_name(
+        &mut self,
+        mapping: HashMap<String, String>,
+        strict: Option<bool>,
+    ) -> Result<String> {
+        let start = self.pos;
+        let value = self.int32()?;
+        let key = value.to_string();
+        match mapping.get(&key) {
+            Some(name) => Ok(name.clone()),
+            None if strict.unwrap_or(false) => {
+                self.pos = start;
+                Err(self.error_reading(&format!("Unknown enum value {}", value), "enumName"))
+            }
+            None => Ok(key),
         }
-        
+    }
+
+    /// Read a NUL-terminated UTF-8 string, consuming the terminator
+    #[napi]
+    pub fn c_string(&mut self) -> Result<String> {
+        let len = self.buffer[self.pos.min(self.buffer.len())..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| self.error_reading("Missing NUL terminator", "cString"))?;
+
         let str_bytes = &self.buffer[self.pos..self.pos + len];
+        let value = String::from_utf8(str_bytes.to_vec())
+            .map_err(|_| self.error_reading("Invalid UTF-8 string", "cString"))?;
+        self.pos += len + 1;
+
+        Ok(value)
+    }
+
+    /// Read a packed repeated bool field (length-delimited run of varints)
+    #[napi]
+    pub fn packed_bool(&mut self) -> Result<Vec<bool>> {
+        // Each element is a full varint, so multi-byte encodings are decoded correctly
+        self.packed_varints("packedBool", |raw| raw != 0)
+    }
+
+    /// Read a packed repeated sfixed32 field into an Int32Array
+    #[napi]
+    pub fn packed_sfixed32(&mut self) -> Result<Int32Array> {
+        let values = self.packed_fixed::<i32>("packedSfixed32")?;
+        Ok(Int32Array::new(values))
+    }
+
+    /// Read a packed repeated fixed64 field into a BigInt64Array without precision loss
+    /// Values above 2^63 - 1 appear as negative; reinterpret with BigInt.asUintN(64, v)
+    #[napi]
+    pub fn packed_fixed64_big(&mut self) -> Result<BigInt64Array> {
+        let values = self.packed_fixed::<i64>("packedFixed64Big")?;
+        Ok(BigInt64Array::new(values))
+    }
+
+    /// Read a packed repeated sint64 field of deltas and return the running sums
+    /// Sums wrap on overflow, matching int64 arithmetic in the producer
+    #[napi]
+    pub fn packed_sint64_cumsum(&mut self) -> Result<BigInt64Array> {
+        let mut sum: i64 = 0;
+        let values = self.packed_varints("packedSint64Cumsum", |raw| {
+            sum = sum.wrapping_add(zigzag_decode64(raw));
+            sum
+        })?;
+        Ok(BigInt64Array::new(values))
+    }
+
+    /// Read a packed repeated uint32 field
+    #[napi]
+    pub fn read_packed_uint32(&mut self) -> Result<Vec<u32>> {
+        self.packed_varints("readPackedUint32", |raw| raw as u32)
+    }
+
+    /// Read a packed repeated sint32 field (ZigZag decoded)
+    #[napi]
+    pub fn read_packed_sint32(&mut self) -> Result<Vec<i32>> {
+        self.packed_varints("readPackedSint32", |raw| zigzag_decode32(raw as u32))
+    }
+
+    /// Read a packed repeated int64 field
+    #[napi]
+    pub fn read_packed_int64(&mut self) -> Result<Vec<i64>> {
+        self.packed_varints("readPackedInt64", |raw| raw as i64)
+    }
+
+    /// Read a packed repeated fixed32 field
+    #[napi]
+    pub fn read_packed_fixed32(&mut self) -> Result<Vec<u32>> {
+        self.packed_fixed("readPackedFixed32")
+    }
+
+    /// Read a packed repeated double field
+    #[napi]
+    pub fn read_packed_double(&mut self) -> Result<Vec<f64>> {
+        self.packed_fixed("readPackedDouble")
+    }
+
+    /// Read `count` consecutive little-endian 64-bit values (no tags or length prefix)
+    /// into a BigInt64Array
+    #[napi]
+    pub fn fixed64_array_big(&mut self, count: u32) -> Result<BigInt64Array> {
+        let len = count as usize * 8;
+        if self.pos + len > self.buffer.len() {
+            return Err(self.error_reading("Unexpected end of buffer", "fixed64ArrayBig"));
+        }
+        let values = from_le_bytes::<i64>(&self.buffer[self.pos..self.pos + len]);
         self.pos += len;
-        
-        String::from_utf8(str_bytes.to_vec())
-            .map_err(|_| Error::from_reason("Invalid UTF-8 string"))
+        Ok(BigInt64Array::new(values))
+    }
+
+    /// Scan the rest of the message without decoding values and report cheap aggregate metrics
+    /// The position is not advanced
+    #[napi]
+    pub fn quick_stats(&self) -> Result<QuickStats> {
+        let region = &self.buffer[self.pos.min(self.buffer.len())..];
+        let fields = scan_fields(region).map_err(|e| self.error_reading(&e, "quickStats"))?;
+        Ok(QuickStats {
+            field_count: fields.len() as u32,
+            max_depth_approx: approx_depth(region, &fields),
+            byte_length: region.len() as u32,
+        })
+    }
+
+    /// Hash the rest of the message field by field, returning a Map keyed by field number
+    /// Each digest covers the bytes after the field's tag (including any length prefix); the
+    /// occurrences of a repeated field are hashed as one concatenation, in order. `algorithm`
+    /// is "sha256" (default) or "fnv1a64". The position is not advanced
+    #[napi(ts_return_type = "Map<number, Buffer>")]
+    pub fn field_hashes(&self, env: Env, algorithm: Option<String>) -> Result<JsObject> {
+        let algorithm = match algorithm.as_deref() {
+            Some(name) => HashAlgorithm::from_name(name)
+                .ok_or_else(|| Error::from_reason("Unknown hash algorithm"))?,
+            None => HashAlgorithm::Sha256,
+        };
+        let region = &self.buffer[self.pos.min(self.buffer.len())..];
+        let fields = scan_fields(region).map_err(|e| self.error_reading(&e, "fieldHashes"))?;
+
+        let mut by_number: BTreeMap<u32, Vec<u8>> = BTreeMap::new();
+        for field in &fields {
+            let mut value_start = field.start;
+            decode_varint32(region, &mut value_start)
+                .map_err(|e| self.error_reading(&e, "fieldHashes"))?;
+            by_number
+                .entry(field.field_number)
+                .or_default()
+                .extend_from_slice(&region[value_start..field.end]);
+        }
+
+        let map: JsFunction = env.get_global()?.get_named_property("Map")?;
+        let hashes = map.new_instance::<JsUnknown>(&[])?;
+        let set: JsFunction = hashes.get_named_property("set")?;
+        for (field_number, bytes) in by_number {
+            let digest = env.create_buffer_with_data(algorithm.digest(&bytes))?.into_unknown();
+            set.call(Some(&hashes), &[env.create_uint32(field_number)?.into_unknown(), digest])?;
+        }
+        Ok(hashes)
+    }
+
+    /// Read a tag and, if its field number is one of the oneof's member fields, return the case
+    /// with the value ready to read. Otherwise (or at end of buffer) the position is restored
+    /// and null is returned
+    #[napi]
+    pub fn oneof(&mut self, case_fields: Uint32Array) -> Result<Option<OneofCase>> {
+        if self.pos >= self.buffer.len() {
+            return Ok(None);
+        }
+        let start = self.pos;
+        let (field_number, wire_type) = match self.read_tag() {
+            Ok(tag) => tag,
+            Err(e) => {
+                self.pos = start;
+                return Err(e);
+            }
+        };
+        if !case_fields.contains(&field_number) {
+            self.pos = start;
+            return Ok(None);
+        }
+        Ok(Some(OneofCase {
+            case_field_number: field_number,
+            wire_type: wire_type as u32,
+        }))
+    }
+
+    /// Decode every remaining field without a schema
+    /// Repeated fields appear once per occurrence, in wire order
+    #[napi]
+    pub fn read_all(&mut self) -> Result<Vec<FieldValue>> {
+        let start = self.pos.min(self.buffer.len());
+        let region = &self.buffer[start..];
+        let fields = scan_fields(region).map_err(|e| self.error_reading(&e, "readAll"))?;
+        let values = fields.iter().map(|f| field_value(region, f)).collect();
+        self.pos = self.buffer.len();
+        Ok(values)
+    }
+
+    /// Decode fields until `terminator_tag` (a raw tag varint) is read, consuming the terminator
+    /// Throws if the buffer ends before the terminator
+    #[napi]
+    pub fn read_until_tag(&mut self, terminator_tag: u32) -> Result<Vec<FieldValue>> {
+        let start = self.pos;
+        let values = self.fields_until_tag(terminator_tag);
+        self.rewind_on_error(start, values)
+    }
+
+    /// Read consecutive unpacked occurrences of `field_number` (tag, value, tag, value, ...)
+    /// starting at a tag. Stops and rewinds at the first tag for a different field.
+    /// `field_type` is a numeric type name or "bool"; 64-bit values are returned as numbers
+    #[napi]
+    pub fn repeated_scalar(
+        &mut self,
+        field_number: u32,
+        field_type: String,
+    ) -> Result<Vec<Either<f64, bool>>> {
+        let ty = FieldType::from_name(&field_type)
+            .filter(|ty| ty.is_scalar())
+            .ok_or_else(|| self.error_reading("Invalid scalar type", "repeatedScalar"))?;
+
+        let start = self.pos;
+        let values = self.scalars_for_field(field_number, ty);
+        self.rewind_on_error(start, values)
+    }
+
+    /// Read one length-delimited map entry (field 1 = key, field 2 = value) and decode both
+    /// `key_type` is an integer type name, "bool" or "string"; `value_type` is any type name.
+    /// Numbers come back as numbers (64-bit ones included), strings as strings, and "bytes" and
+    /// "message" values as Buffers to decode separately. A missing key or value is its type's
+    /// zero value (0, false, "" or an empty Buffer) and other fields in the entry are skipped
+    #[napi]
+    pub fn read_map_entry(&mut self, key_type: String, value_type: String) -> Result<MapEntry> {
+        let key_ty = FieldType::from_name(&key_type)
+            .filter(|ty| ty.is_map_key())
+            .ok_or_else(|| self.error_reading("Invalid map key type", "readMapEntry"))?;
+        let value_ty = FieldType::from_name(&value_type)
+            .ok_or_else(|| self.error_reading("Invalid map value type", "readMapEntry"))?;
+
+        let start = self.pos;
+        let entry = self.map_entry(key_ty, value_ty);
+        self.rewind_on_error(start, entry)
+    }
+
+    /// Peek the next tag without consuming it and check whether it is for `field_number`
+    /// Returns false at end of buffer
+    #[napi]
+    pub fn next_field_number_is(&self, field_number: u32) -> Result<bool> {
+        if self.pos >= self.buffer.len() {
+            return Ok(false);
+        }
+        let mut pos = self.pos;
+        let tag = decode_varint32(&self.buffer, &mut pos)
+            .map_err(|e| self.error_reading(&e, "nextFieldNumberIs"))?;
+        Ok(tag >> 3 == field_number)
+    }
+
+    /// Read the fields of a group whose StartGroup tag for `field_number` was just read,
+    /// calling `callback(fieldNumber, wireType, value)` for each and consuming the EndGroup tag.
+    /// Values are decoded as in readAll; a nested group's value is a Buffer holding its body
+    /// and EndGroup tag, so it can be read with a new Reader and readGroupFields. The callback
+    /// must not use this Reader; its position and limit are restored after each call
+    #[napi]
+    pub fn read_group_fields(
+        &mut self,
+        env: Env,
+        field_number: u32,
+        callback: JsFunction,
+    ) -> Result<&Self> {
+        let start = self.pos;
+        // Validate the whole group first so a malformed group never reaches the callback
+        let (body_end, end) =
+            scan_group(&self.buffer, start, Some(field_number), self.depth + 1, self.max_depth)
+                .map_err(|e| self.error_reading(&e, "readGroupFields"))?;
+
+        // Snapshot the window so a callback that moves or limits this Reader cannot change
+        // the bytes the walk slices
+        let window = self.buffer.clone();
+        let last_tag_pos = self.last_tag_pos;
+        let mut pos = start;
+        while pos < body_end {
+            let tag_start = pos;
+            let tag = decode_varint32(&self.buffer, &mut pos)
+                .map_err(|e| self.error_reading(&e, "readGroupFields"))?;
+            let (number, wire_type) = decode_tag(tag);
+            let value = if wire_type == Some(WireType::StartGroup) {
+                let nested_end =
+                    scan_group(&self.buffer, pos, Some(number), self.depth + 2, self.max_depth)
+                        .map_err(|e| self.error_reading(&e, "readGroupFields"))?
+                        .1;
+                let body = Buffer::from(self.buffer[pos..nested_end].to_vec());
+                pos = nested_end;
+                FieldValue {
+                    field_number: number,
+                    wire_type: WireType::StartGroup as u32,
+                    value: Either3::C(body),
+                }
+            } else {
+                let field = scan_field(&self.buffer, tag_start)
+                    .map_err(|e| self.error_reading(&e, "readGroupFields"))?;
+                pos = field.end;
+                field_value(&self.buffer, &field)
+            };
+
+            let result = callback.call(
+                None,
+                &[
+                    to_js(&env, value.field_number)?,
+                    to_js(&env, value.wire_type)?,
+                    to_js(&env, value.value)?,
+                ],
+            );
+            self.buffer = window.clone();
+            self.pos = start;
+            self.last_tag_pos = last_tag_pos;
+            result?;
+        }
+
+        self.pos = end;
+        Ok(self)
+    }
+
+    /// Parse the remaining bytes as a message of the compiled schema, calling handlers as
+    /// each part is reached instead of building an object: `field(name, fieldNumber, value)`
+    /// for every non-message value (once per element of a packed field), and
+    /// `startMessage(name, fieldNumber)` / `endMessage(name, fieldNumber)` around each nested
+    /// message's fields. Any handler may be omitted. Unknown fields are skipped unless the schema
+    /// rejects them. Handlers must not use this Reader
+    #[napi]
+    pub fn parse_events(
+        &mut self,
+        env: Env,
+        schema: &SchemaHandle,
+        handlers: JsObject,
+    ) -> Result<&Self> {
+        let handlers = EventHandlers {
+            env,
+            field: handlers.get("field")?,
+            start_message: handlers.get("startMessage")?,
+            end_message: handlers.get("endMessage")?,
+        };
+        let start = self.pos.min(self.buffer.len());
+        // Errors pass through unchanged so an exception thrown by a handler propagates as is
+        emit_events(&handlers, &schema.schema, &self.buffer[start..])?;
+        self.pos = self.buffer.len();
+        Ok(self)
+    }
+
+    /// Decode the remaining bytes with a compiled schema, as decodeWith does, except that
+    /// top-level fields whose number is in `denylist` are skipped without being decoded, so
+    /// their values never reach JS. Consumes the rest of the buffer
+    #[napi]
+    pub fn decode_excluding(
+        &mut self,
+        env: Env,
+        schema: &SchemaHandle,
+        denylist: Uint32Array,
+    ) -> Result<JsObject> {
+        let start = self.pos.min(self.buffer.len());
+        let values =
+            decode_message_excluding(&schema.schema, &self.buffer[start..], denylist.as_ref())
+                .map_err(|e| self.error_reading(&e, "decodeExcluding"))?;
+        self.pos = self.buffer.len();
+        message_to_js(&env, &schema.schema, values)
+    }
+
+    /// Decode the remaining bytes with a compiled schema into element `row` of caller-owned
+    /// typed array columns, keyed by field number in `columns`, so a stream of messages can
+    /// fill preallocated columns one message at a time. Column types match
+    /// decodeBatchColumnar; fields without a column are ignored and absent fields store the
+    /// type's default. Consumes the rest of the buffer
+    #[napi]
+    pub fn collect_into(
+        &mut self,
+        schema: &SchemaHandle,
+        columns: JsObject,
+        row: u32,
+    ) -> Result<&Self> {
+        let start = self.pos.min(self.buffer.len());
+        let values = decode_message(&schema.schema, &self.buffer[start..])
+            .map_err(|e| self.error_reading(&e, "collectInto"))?;
+        store_row(&schema.schema, &values, &columns, row)?;
+        self.pos = self.buffer.len();
+        Ok(self)
     }
 
     /// Skip a specific number of bytes
@@ -223,54 +921,599 @@ impl Reader {
     pub fn skip(&mut self, length: u32) -> Result<&Self> {
         let len = length as usize;
         if self.pos + len > self.buffer.len() {
-            return Err(Error::from_reason("Unexpected end of buffer"));
+            return Err(self.error_reading("Unexpected end of buffer", "skip"));
         }
         self.pos += len;
         Ok(self)
     }
 
     /// Skip a field based on wire type
+    /// A group is skipped through its matching EndGroup, nested groups included; when the
+    /// StartGroup tag was read with tag() the EndGroup must carry the same field number
     #[napi]
     pub fn skip_type(&mut self, wire_type: u32) -> Result<&Self> {
-        let wt = WireType::from_u8(wire_type as u8)
-            .ok_or_else(|| Error::from_reason("Invalid wire type"))?;
+        let wt = u8::try_from(wire_type)
+            .ok()
+            .and_then(WireType::from_u8)
+            .ok_or_else(|| self.error_reading("Invalid wire type", "skipType"))?;
         
         match wt {
             WireType::Varint => {
-                decode_varint64(&self.buffer, &mut self.pos)
-                    .map_err(|e| Error::from_reason(e))?;
+                self.varint("skipType", decode_varint64)?;
             }
             WireType::Fixed64 => {
                 if self.pos + 8 > self.buffer.len() {
-                    return Err(Error::from_reason("Unexpected end of buffer"));
+                    return Err(self.error_reading("Unexpected end of buffer", "skipType"));
                 }
                 self.pos += 8;
             }
             WireType::LengthDelimited => {
-                let len = decode_varint32(&self.buffer, &mut self.pos)
-                    .map_err(|e| Error::from_reason(e))? as usize;
-                if self.pos + len > self.buffer.len() {
-                    return Err(Error::from_reason("Unexpected end of buffer"));
-                }
-                self.pos += len;
+                self.length_delimited("skipType")?;
             }
             WireType::Fixed32 => {
                 if self.pos + 4 > self.buffer.len() {
-                    return Err(Error::from_reason("Unexpected end of buffer"));
+                    return Err(self.error_reading("Unexpected end of buffer", "skipType"));
                 }
                 self.pos += 4;
             }
-            WireType::StartGroup | WireType::EndGroup => {
-                return Err(Error::from_reason("Groups are not supported"));
+            WireType::StartGroup => {
+                let field_number = self.open_group_number();
+                self.pos =
+                    scan_group(&self.buffer, self.pos, field_number, self.depth + 1, self.max_depth)
+                        .map_err(|e| self.error_reading(&e, "skipType"))?
+                        .1;
+            }
+            WireType::EndGroup => {
+                return Err(self.error_reading("Unexpected end group", "skipType"));
             }
         }
         
         Ok(self)
     }
 
+    /// Skip the next `count` fields, reading each tag and skipping its value in one call
+    /// Groups are skipped whole; fails if the buffer ends before `count` fields are skipped
+    #[napi]
+    pub fn skip_fields(&mut self, count: u32) -> Result<&Self> {
+        let start = self.pos;
+        let skipped = self.skip_field_run(count);
+        self.rewind_on_error(start, skipped)?;
+        Ok(self)
+    }
+
+    /// Advance to the end of the readable region (the limit, if one is set) regardless of the
+    /// current position. For a reader over a nested message this jumps past whatever the
+    /// handler left unread
+    #[napi]
+    pub fn skip_to_end(&mut self) -> &Self {
+        self.pos = self.buffer.len();
+        self
+    }
+
+    /// Confine reading to the first `max_pos` bytes; reads past it fail as end of buffer
+    /// even though more bytes exist. Replaces any earlier limit
+    #[napi]
+    pub fn limit(&mut self, max_pos: u32) -> Result<&Self> {
+        let max_pos = max_pos as usize;
+        if max_pos > self.buffer.full_len() {
+            return Err(self.error_reading("Limit out of bounds", "limit"));
+        }
+        if max_pos < self.pos {
+            return Err(self.error_reading("Limit is before the current position", "limit"));
+        }
+        // Shrinking the visible region keeps every bounds check that uses the buffer length correct
+        self.buffer.set_limit(Some(max_pos));
+        Ok(self)
+    }
+
+    /// Remove the limit set with limit(), making the whole buffer readable again
+    #[napi]
+    pub fn clear_limit(&mut self) -> &Self {
+        self.buffer.set_limit(None);
+        self
+    }
+
+    /// Move back to the start of the most recently read field tag (as read by oneof() and the
+    /// other tag-reading methods), e.g. to re-dispatch or capture a field whose value failed
+    /// validation
+    #[napi]
+    pub fn rewind_field(&mut self) -> Result<&Self> {
+        match self.last_tag_pos {
+            Some(pos) if pos <= self.buffer.len() => {
+                self.pos = pos;
+                Ok(self)
+            }
+            _ => Err(self.error_reading("No field tag to rewind to", "rewindField")),
+        }
+    }
+
     /// Get current position
     #[napi]
     pub fn pos(&self) -> u32 {
         self.pos as u32
     }
+
+    /// Get current position (file-style alias of pos)
+    #[napi]
+    pub fn tell(&self) -> u32 {
+        self.pos as u32
+    }
+
+    /// Move the position like fseek
+    /// `whence` is 0 for the start (default), 1 for the current position, 2 for the end
+    /// (the limit, if one is set)
+    #[napi]
+    pub fn seek(&mut self, offset: i64, whence: Option<u32>) -> Result<&Self> {
+        let base = match whence.unwrap_or(SEEK_SET) {
+            SEEK_SET => 0,
+            SEEK_CUR => self.pos as i64,
+            SEEK_END => self.buffer.len() as i64,
+            _ => return Err(self.error_reading("Invalid whence", "seek")),
+        };
+        let target = base + offset;
+        if target < 0 || target > self.buffer.len() as i64 {
+            return Err(self.error_reading("Seek position out of bounds", "seek"));
+        }
+        self.pos = target as usize;
+        Ok(self)
+    }
+
+    /// Move back to the start of the buffer to read it again (equivalent to seek(0)); any
+    /// limit stays in place
+    #[napi]
+    pub fn reset(&mut self) -> &Self {
+        self.pos = 0;
+        self.last_tag_pos = None;
+        self
+    }
+
+    /// Length of the readable region in bytes (the limit, if one is set)
+    #[napi]
+    pub fn len(&self) -> u32 {
+        self.buffer.len() as u32
+    }
+
+    /// Whether the readable region is empty (a limit of 0 counts as empty)
+    #[napi]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Number of bytes left to read, never negative
+    #[napi]
+    pub fn remaining(&self) -> u32 {
+        self.buffer.len().saturating_sub(self.pos) as u32
+    }
+
+    /// Create an independent Reader at the same position, with the same limit and settings,
+    /// e.g. to try one interpretation of the following bytes and fall back to another. The
+    /// Buffer is shared rather than copied
+    #[napi(js_name = "clone")]
+    pub fn clone_reader(&self) -> Reader {
+        Reader {
+            buffer: self.buffer.clone(),
+            pos: self.pos,
+            error_context: self.error_context,
+            depth: self.depth,
+            max_depth: self.max_depth,
+            max_field_length: self.max_field_length,
+            strict_varints: self.strict_varints,
+            last_tag_pos: self.last_tag_pos,
+        }
+    }
+}
+
+impl Reader {
+    /// Read a field tag, rejecting unknown wire types and field number 0
+    /// The position only advances when the tag is valid
+    fn read_tag(&mut self) -> Result<(u32, WireType)> {
+        let mut pos = self.pos;
+        let tag =
+            decode_varint32(&self.buffer, &mut pos).map_err(|e| self.error_reading(&e, "tag"))?;
+        if self.is_non_canonical(self.pos, pos) {
+            return Err(self.error_reading("Non-canonical varint", "tag"));
+        }
+        let (field_number, wire_type) = decode_tag(tag);
+        let wire_type = wire_type.ok_or_else(|| self.error_reading("Invalid wire type", "tag"))?;
+        if field_number == 0 {
+            return Err(self.error_reading("Invalid field number 0", "tag"));
+        }
+        self.last_tag_pos = Some(self.pos);
+        self.pos = pos;
+        Ok((field_number, wire_type))
+    }
+
+    /// Read a single numeric or bool value of the given type, naming `what` if it is not scalar
+    fn scalar(&mut self, ty: FieldType, what: &str) -> Result<Either<f64, bool>> {
+        let value = match ty {
+            FieldType::Double => self.double()?,
+            FieldType::Float => self.float()?,
+            FieldType::Int32 | FieldType::Enum => self.int32()? as f64,
+            FieldType::Int64 => self.int64()? as f64,
+            FieldType::Uint32 => self.uint32()? as f64,
+            FieldType::Uint64 => self.uint64()? as u64 as f64,
+            FieldType::Sint32 => self.sint32()? as f64,
+            FieldType::Sint64 => self.sint64()? as f64,
+            FieldType::Fixed32 => self.fixed32()? as f64,
+            FieldType::Fixed64 => self.fixed64()? as u64 as f64,
+            FieldType::Sfixed32 => self.sfixed32()? as f64,
+            FieldType::Sfixed64 => self.sfixed64()? as f64,
+            FieldType::Bool => return Ok(Either::B(self.bool()?)),
+            FieldType::String | FieldType::Bytes | FieldType::Message => {
+                return Err(self.error_reading("Invalid scalar type", what));
+            }
+        };
+        Ok(Either::A(value))
+    }
+
+    /// Read the key or value of a map entry, whose tag with `wire_type` was just read
+    fn map_value(&mut self, ty: FieldType, wire_type: WireType) -> Result<MapValue> {
+        if wire_type != ty.wire_type() {
+            let reason = "Wire type does not match map entry type";
+            return Err(self.error_reading(reason, "readMapEntry"));
+        }
+        Ok(match ty {
+            FieldType::String => Either4::C(self.string()?),
+            FieldType::Bytes | FieldType::Message => Either4::D(self.bytes()?),
+            _ => match self.scalar(ty, "readMapEntry")? {
+                Either::A(number) => Either4::A(number),
+                Either::B(flag) => Either4::B(flag),
+            },
+        })
+    }
+
+    /// Read the length prefix of a packed field and return the offset where its payload ends
+    /// On failure the position stays at the start of the length prefix
+    fn packed_end(&mut self, what: &str) -> Result<usize> {
+        let start = self.pos;
+        let len = self.varint(what, decode_varint32)? as usize;
+        if self.pos + len > self.buffer.len() {
+            self.pos = start;
+            return Err(self.error_reading("Unexpected end of buffer", what));
+        }
+        Ok(self.pos + len)
+    }
+
+    /// Read a packed run of varints, converting each raw value
+    /// An element that runs past the declared length is an error, not a read into the next field.
+    /// The error names the offset of the bad element; the position returns to the length prefix
+    fn packed_varints<T>(&mut self, what: &str, convert: impl FnMut(u64) -> T) -> Result<Vec<T>> {
+        let start = self.pos;
+        let values = self.packed_varint_run(what, convert);
+        self.rewind_on_error(start, values)
+    }
+
+    /// Decode the elements of a packed run of varints for packed_varints
+    fn packed_varint_run<T>(
+        &mut self,
+        what: &str,
+        mut convert: impl FnMut(u64) -> T,
+    ) -> Result<Vec<T>> {
+        let end = self.packed_end(what)?;
+        let mut values = Vec::new();
+        while self.pos < end {
+            let mut pos = self.pos;
+            let raw = decode_varint64(&self.buffer[..end], &mut pos).map_err(|e| {
+                if e == ERR_NEED_MORE_DATA {
+                    self.error_reading("Packed varint runs past the end of the field", what)
+                } else {
+                    self.error_reading(&e, what)
+                }
+            })?;
+            if self.is_non_canonical(self.pos, pos) {
+                return Err(self.error_reading("Non-canonical varint in packed field", what));
+            }
+            self.pos = pos;
+            values.push(convert(raw));
+        }
+        Ok(values)
+    }
+
+    /// Run the rest of readUntilTag, leaving the position wherever a failure happened
+    fn fields_until_tag(&mut self, terminator_tag: u32) -> Result<Vec<FieldValue>> {
+        let mut values = Vec::new();
+        loop {
+            if self.pos >= self.buffer.len() {
+                return Err(self.error_reading("Missing terminator tag", "readUntilTag"));
+            }
+            let mut tag_end = self.pos;
+            let tag = decode_varint32(&self.buffer, &mut tag_end)
+                .map_err(|e| self.error_reading(&e, "readUntilTag"))?;
+            if tag == terminator_tag {
+                self.pos = tag_end;
+                return Ok(values);
+            }
+            let field = scan_field(&self.buffer, self.pos)
+                .map_err(|e| self.error_reading(&e, "readUntilTag"))?;
+            values.push(field_value(&self.buffer, &field));
+            self.pos = field.end;
+        }
+    }
+
+    /// Run the rest of repeatedScalar, leaving the position wherever a failure happened
+    fn scalars_for_field(
+        &mut self,
+        field_number: u32,
+        ty: FieldType,
+    ) -> Result<Vec<Either<f64, bool>>> {
+        let mut values = Vec::new();
+        while self.pos < self.buffer.len() {
+            let start = self.pos;
+            let (number, wire_type) = self.read_tag()?;
+            if number != field_number {
+                self.pos = start;
+                break;
+            }
+            if wire_type != ty.wire_type() {
+                self.pos = start;
+                let reason = "Wire type does not match field type";
+                return Err(self.error_reading(reason, "repeatedScalar"));
+            }
+            values.push(self.scalar(ty, "repeatedScalar")?);
+        }
+        Ok(values)
+    }
+
+    /// Run the rest of readMapEntry, leaving the position wherever a failure happened
+    fn map_entry(&mut self, key_ty: FieldType, value_ty: FieldType) -> Result<MapEntry> {
+        let mut entry = self.sub_reader()?;
+        let (mut key, mut value) = (None, None);
+        while entry.pos < entry.buffer.len() {
+            let (number, wire_type) = entry.read_tag()?;
+            match number {
+                1 => key = Some(entry.map_value(key_ty, wire_type)?),
+                2 => value = Some(entry.map_value(value_ty, wire_type)?),
+                _ => {
+                    entry.skip_type(wire_type as u32)?;
+                }
+            }
+        }
+        Ok(MapEntry {
+            key: key.unwrap_or_else(|| zero_map_value(key_ty)),
+            value: value.unwrap_or_else(|| zero_map_value(value_ty)),
+        })
+    }
+
+    /// Run the rest of skipFields, leaving the position wherever a failure happened
+    fn skip_field_run(&mut self, count: u32) -> Result<()> {
+        for skipped in 0..count {
+            if self.pos >= self.buffer.len() {
+                let reason = format!(
+                    "Unexpected end of buffer after skipping {} of {} fields",
+                    skipped, count
+                );
+                return Err(self.error_reading(&reason, "skipFields"));
+            }
+            let (_, wire_type) = self.read_tag()?;
+            self.skip_type(wire_type as u32)?;
+        }
+        Ok(())
+    }
+
+    /// Move back to `start` if `result` is an error, so a failed multi-step read leaves the
+    /// position where the call began. The error keeps the offset where the failure happened
+    fn rewind_on_error<T>(&mut self, start: usize, result: Result<T>) -> Result<T> {
+        if result.is_err() {
+            self.pos = start;
+        }
+        result
+    }
+
+    /// Field number of the StartGroup tag ending at the current position, if that tag was read
+    /// with tag() or another tag-reading method (a tag read with uint32() is not tracked)
+    fn open_group_number(&self) -> Option<u32> {
+        let mut pos = self.last_tag_pos?;
+        let tag = decode_varint32(&self.buffer, &mut pos).ok()?;
+        match decode_tag(tag) {
+            (number, Some(WireType::StartGroup)) if pos == self.pos => Some(number),
+            _ => None,
+        }
+    }
+
+    /// Read a varint with `decode`, naming `what` in the error
+    /// On failure the position stays at the start of the varint, which is the offset reported
+    fn varint<T>(
+        &mut self,
+        what: &str,
+        decode: fn(&[u8], &mut usize) -> std::result::Result<T, String>,
+    ) -> Result<T> {
+        let mut pos = self.pos;
+        let value = decode(&self.buffer, &mut pos).map_err(|e| self.error_reading(&e, what))?;
+        if self.is_non_canonical(self.pos, pos) {
+            return Err(self.error_reading("Non-canonical varint", what));
+        }
+        self.pos = pos;
+        Ok(value)
+    }
+
+    /// Whether strict varints are enabled and the varint at `start..end` is longer than needed
+    /// A minimal multi-byte varint never ends in 0x00, since that byte contributes no bits
+    fn is_non_canonical(&self, start: usize, end: usize) -> bool {
+        self.strict_varints && end - start > 1 && self.buffer[end - 1] == 0
+    }
+
+    /// Read a length prefix and return the range of the payload after it, moving past both
+    /// Lengths above the maximum field length are rejected before anything is sliced. On
+    /// failure the position stays at the start of the length prefix
+    fn length_delimited(&mut self, what: &str) -> Result<Range<usize>> {
+        let start = self.pos;
+        let len = self.varint(what, decode_varint32)? as usize;
+        if len > self.max_field_length {
+            self.pos = start;
+            let reason = format!(
+                "Field length {} exceeds the maximum of {}",
+                len, self.max_field_length
+            );
+            return Err(self.error_reading(&reason, what));
+        }
+        if self.pos + len > self.buffer.len() {
+            self.pos = start;
+            return Err(self.error_reading("Unexpected end of buffer", what));
+        }
+        self.pos += len;
+        Ok(self.pos - len..self.pos)
+    }
+
+    /// Read one little-endian fixed-width value, naming `what` in the error
+    fn fixed<T: FixedLe>(&mut self, what: &str) -> Result<T> {
+        if self.pos + T::SIZE > self.buffer.len() {
+            return Err(self.error_reading("Unexpected end of buffer", what));
+        }
+        let value = T::from_le_slice(&self.buffer[self.pos..]);
+        self.pos += T::SIZE;
+        Ok(value)
+    }
+
+    /// Read a packed run of fixed-width values
+    /// On failure the position stays at the start of the length prefix
+    fn packed_fixed<T: FixedLe>(&mut self, what: &str) -> Result<Vec<T>> {
+        let start = self.pos;
+        let end = self.packed_end(what)?;
+        if !(end - self.pos).is_multiple_of(T::SIZE) {
+            self.pos = start;
+            return Err(self.error_reading("Packed field length does not match element size", what));
+        }
+        let values = from_le_bytes::<T>(&self.buffer[self.pos..end]);
+        self.pos = end;
+        Ok(values)
+    }
+
+    /// Build a decode error naming the read that failed, e.g.
+    /// "Unexpected end of buffer at offset 37 while reading fixed64"
+    fn error_reading(&self, reason: &str, what: &str) -> Error {
+        self.error_with_context(format!("{} at offset {} while reading {}", reason, self.pos, what))
+    }
+
+    /// Finish a decode error, appending the surrounding bytes when error context is enabled
+    fn error_with_context(&self, message: String) -> Error {
+        if !self.error_context {
+            return Error::from_reason(message);
+        }
+
+        let start = self.pos.saturating_sub(ERROR_CONTEXT_BYTES).min(self.buffer.len());
+        let end = (self.pos + ERROR_CONTEXT_BYTES).min(self.buffer.len());
+        let hex = self.buffer[start..end]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Error::from_reason(format!("{}, context [0x{:x}..0x{:x}]: {}", message, start, end, hex))
+    }
+}
+
+/// Handler functions passed to Reader.parseEvents
+struct EventHandlers {
+    env: Env,
+    field: Option<JsFunction>,
+    start_message: Option<JsFunction>,
+    end_message: Option<JsFunction>,
+}
+
+impl EventHandlers {
+    /// Call startMessage or endMessage for a nested message field, if provided
+    fn message_event(&self, handler: &Option<JsFunction>, field: &CompiledField) -> Result<()> {
+        if let Some(handler) = handler {
+            handler.call(
+                None,
+                &[
+                    to_js(&self.env, field.name.as_str())?,
+                    to_js(&self.env, field.field_number)?,
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Walk the fields of one message in `buf`, recursing into nested messages
+fn emit_events(handlers: &EventHandlers, schema: &CompiledSchema, buf: &[u8]) -> Result<()> {
+    let mut pos = 0;
+    while pos < buf.len() {
+        let span = scan_field(buf, pos).map_err(Error::from_reason)?;
+        pos = span.end;
+        let Some(index) = schema.decode_index(span.field_number).map_err(Error::from_reason)?
+        else {
+            continue;
+        };
+        let field = &schema.fields[index];
+
+        if let Some(nested) = &field.message {
+            if span.wire_type != WireType::LengthDelimited {
+                return Err(Error::from_reason(format!(
+                    "Wire type mismatch for field {}",
+                    span.field_number
+                )));
+            }
+            handlers.message_event(&handlers.start_message, field)?;
+            emit_events(handlers, nested, &buf[span.value_start..span.end])?;
+            handlers.message_event(&handlers.end_message, field)?;
+            continue;
+        }
+
+        let mut values = Vec::new();
+        span_values(field, buf, &span, |value| values.push(value)).map_err(Error::from_reason)?;
+        if let Some(handler) = &handlers.field {
+            for value in values {
+                handler.call(
+                    None,
+                    &[
+                        to_js(&handlers.env, field.name.as_str())?,
+                        to_js(&handlers.env, field.field_number)?,
+                        value_to_js(&handlers.env, field, value)?,
+                    ],
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Value of a map key or value missing from its entry
+fn zero_map_value(ty: FieldType) -> MapValue {
+    match ty {
+        FieldType::Bool => Either4::B(false),
+        FieldType::String => Either4::C(String::new()),
+        FieldType::Bytes | FieldType::Message => Either4::D(Buffer::from(Vec::new())),
+        _ => Either4::A(0.0),
+    }
+}
+
+/// Convert a Rust value into a JS value for passing to a callback
+fn to_js<T: ToNapiValue>(env: &Env, value: T) -> Result<JsUnknown> {
+    unsafe {
+        let raw = T::to_napi_value(env.raw(), value)?;
+        Ok(JsUnknown::from_raw_unchecked(env.raw(), raw))
+    }
+}
+
+/// Decode a scanned field's value to its natural JS type
+fn field_value(buffer: &[u8], field: &FieldSpan) -> FieldValue {
+    let bytes = &buffer[field.value_start..field.end];
+    let value = match field.wire_type {
+        WireType::Varint => {
+            let mut pos = 0;
+            // The span was produced by a successful scan, so the varint is well-formed
+            let val = decode_varint64(bytes, &mut pos).unwrap_or_default();
+            if val <= MAX_SAFE_INTEGER {
+                Either3::A(val as f64)
+            } else {
+                Either3::B(BigInt::from(val))
+            }
+        }
+        WireType::Fixed32 => {
+            Either3::A(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64)
+        }
+        WireType::Fixed64 => {
+            let mut raw = [0u8; 8];
+            raw.copy_from_slice(bytes);
+            Either3::B(BigInt::from(u64::from_le_bytes(raw)))
+        }
+        _ => Either3::C(Buffer::from(bytes.to_vec())),
+    };
+    FieldValue {
+        field_number: field.field_number,
+        wire_type: field.wire_type as u32,
+        value,
+    }
 }
@@ -0,0 +1,173 @@
+//! Structural scanning of encoded messages without decoding values
+
+use crate::varint::{decode_varint32, decode_varint64, ERR_NEED_MORE_DATA};
+use crate::wire_type::{decode_tag, WireType};
+
+/// Maximum nesting explored when approximating message depth
+const MAX_APPROX_DEPTH: u32 = 32;
+
+/// Deepest group nesting accepted when scanning a group field, as for a Reader by default
+pub const MAX_GROUP_DEPTH: u32 = 64;
+
+/// Location of a single field within an encoded message
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpan {
+    pub field_number: u32,
+    pub wire_type: WireType,
+    /// Offset of the first byte of the tag
+    pub start: usize,
+    /// Offset of the first byte of the value (after any length prefix)
+    pub value_start: usize,
+    /// Offset one past the last byte of the value
+    /// For a group the value is its body followed by the matching EndGroup tag
+    pub end: usize,
+}
+
+/// Scan the field starting at `offset` and return where its tag and value lie
+pub fn scan_field(buffer: &[u8], offset: usize) -> Result<FieldSpan, String> {
+    let mut pos = offset;
+    let tag = decode_varint32(buffer, &mut pos)?;
+    let (field_number, wire_type) = decode_tag(tag);
+    // Field number 0 can never be valid, so reject it before waiting on the rest of the field
+    if field_number == 0 {
+        return Err("Invalid field number 0".to_string());
+    }
+    let wire_type = wire_type.ok_or_else(|| "Invalid wire type".to_string())?;
+
+    let value_start;
+    match wire_type {
+        WireType::Varint => {
+            value_start = pos;
+            decode_varint64(buffer, &mut pos)?;
+        }
+        WireType::Fixed64 => {
+            value_start = pos;
+            pos += 8;
+        }
+        WireType::LengthDelimited => {
+            let len = decode_varint32(buffer, &mut pos)? as usize;
+            value_start = pos;
+            pos += len;
+        }
+        WireType::Fixed32 => {
+            value_start = pos;
+            pos += 4;
+        }
+        WireType::StartGroup => {
+            value_start = pos;
+            pos = scan_group(buffer, pos, Some(field_number), 1, MAX_GROUP_DEPTH)?.1;
+        }
+        WireType::EndGroup => {
+            return Err("Unexpected end group".to_string());
+        }
+    }
+
+    if pos > buffer.len() {
+        return Err(ERR_NEED_MORE_DATA.to_string());
+    }
+
+    Ok(FieldSpan {
+        field_number,
+        wire_type,
+        start: offset,
+        value_start,
+        end: pos,
+    })
+}
+
+/// Find the end of a group whose StartGroup tag for `field_number` ends at `offset`
+/// Returns the offset of the matching EndGroup tag and the offset just past it.
+/// `depth` is the nesting of this group (1 for a top-level group) and input nested deeper than
+/// `max_depth` is rejected. When the field number is not known, the first EndGroup at this
+/// depth closes the group whatever its number
+pub fn scan_group(
+    buffer: &[u8],
+    offset: usize,
+    field_number: Option<u32>,
+    depth: u32,
+    max_depth: u32,
+) -> Result<(usize, usize), String> {
+    if depth > max_depth {
+        return Err("Group nesting too deep".to_string());
+    }
+
+    let mut pos = offset;
+    loop {
+        let tag_start = pos;
+        let tag = decode_varint32(buffer, &mut pos)?;
+        match decode_tag(tag) {
+            (0, _) => return Err("Invalid field number 0".to_string()),
+            (number, Some(WireType::EndGroup))
+                if field_number.is_none() || field_number == Some(number) =>
+            {
+                return Ok((tag_start, pos));
+            }
+            (_, Some(WireType::EndGroup)) => return Err("Mismatched end group".to_string()),
+            (number, Some(WireType::StartGroup)) => {
+                pos = scan_group(buffer, pos, Some(number), depth + 1, max_depth)?.1;
+            }
+            _ => pos = scan_field(buffer, tag_start)?.end,
+        }
+    }
+}
+
+/// Scan every top-level field in `buffer`
+pub fn scan_fields(buffer: &[u8]) -> Result<Vec<FieldSpan>, String> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while pos < buffer.len() {
+        let field = scan_field(buffer, pos)?;
+        pos = field.end;
+        fields.push(field);
+    }
+    Ok(fields)
+}
+
+/// Walk the message that runs from `offset` to the end of `buffer` and return its length
+/// Returns None when no bytes are available yet or the buffer ends part-way through a
+/// field (more data is needed); structurally invalid input is an error
+pub fn message_length(buffer: &[u8], offset: usize) -> Result<Option<usize>, String> {
+    if offset > buffer.len() {
+        return Err("Offset out of bounds".to_string());
+    }
+    if offset == buffer.len() {
+        return Ok(None);
+    }
+
+    let mut pos = offset;
+    while pos < buffer.len() {
+        match scan_field(buffer, pos) {
+            Ok(field) => pos = field.end,
+            Err(e) if e == ERR_NEED_MORE_DATA => return Ok(None),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(Some(pos - offset))
+}
+
+/// Approximate the nesting depth of a message whose top-level fields are `fields`
+/// Length-delimited payloads that scan cleanly as messages are counted as sub-messages,
+/// so strings or bytes that happen to look like messages can inflate the result
+pub fn approx_depth(buffer: &[u8], fields: &[FieldSpan]) -> u32 {
+    if fields.is_empty() {
+        return 0;
+    }
+    nested_depth(buffer, fields, 1)
+}
+
+fn nested_depth(buffer: &[u8], fields: &[FieldSpan], depth: u32) -> u32 {
+    let mut max = depth;
+    if depth >= MAX_APPROX_DEPTH {
+        return max;
+    }
+    for field in fields {
+        if field.wire_type != WireType::LengthDelimited || field.end == field.value_start {
+            continue;
+        }
+        let payload = &buffer[field.value_start..field.end];
+        if let Ok(children) = scan_fields(payload) {
+            max = max.max(nested_depth(payload, &children, depth + 1));
+        }
+    }
+    max
+}
@@ -0,0 +1,919 @@
+//! Schema descriptions and schema-driven encoding and decoding
+//!
+//! A schema is an array of field descriptors. `compile_schema` resolves type names, tags and
+//! nested message schemas once, so later calls never re-interpret the descriptor objects.
+
+use crate::field_type::FieldType;
+use crate::reader::MAX_SAFE_INTEGER;
+use crate::scan::{scan_field, scan_fields, FieldSpan};
+use crate::varint::{
+    decode_varint64, encode_varint32, encode_varint64, varint_len, zigzag_decode32,
+    zigzag_decode64, zigzag_encode32, zigzag_encode64,
+};
+use crate::wire_type::{encode_tag, WireType, MAX_FIELD_NUMBER};
+use crate::writer::{checked_integer, I32_RANGE, I64_RANGE, U32_RANGE, U64_RANGE};
+use napi::bindgen_prelude::*;
+use napi::{Env, JsObject, JsUnknown};
+use napi_derive::napi;
+use std::collections::HashMap;
+
+/// Largest field number looked up through the dense index; larger numbers use a linear search
+const MAX_DENSE_FIELD_NUMBER: u32 = 256;
+
+/// A field in a JS schema description
+#[napi(object)]
+pub struct FieldDescriptor {
+    /// Property name on the JS object
+    pub name: String,
+    pub field_number: u32,
+    /// `.proto` type name ("int32", "string", "message", ...)
+    #[napi(js_name = "type")]
+    pub field_type: String,
+    pub repeated: Option<bool>,
+    /// Whether a repeated numeric or bool field is packed (defaults to true, as in proto3)
+    pub packed: Option<bool>,
+    /// Fields of the nested message when `type` is "message"
+    pub fields: Option<Vec<FieldDescriptor>>,
+    /// Enum value numbers by name when `type` is "enum", used by the JSON converters
+    pub enum_values: Option<HashMap<String, i32>>,
+}
+
+/// A field with its type resolved and tag pre-encoded
+pub struct CompiledField {
+    pub name: String,
+    pub field_number: u32,
+    pub ty: FieldType,
+    pub repeated: bool,
+    pub packed: bool,
+    /// Encoded tag written before each value (or before the packed payload)
+    pub tag: Vec<u8>,
+    pub message: Option<CompiledSchema>,
+    /// Enum values as (name, number), sorted by number
+    pub enum_values: Vec<(String, i32)>,
+}
+
+/// A message schema ready for encoding and decoding
+pub struct CompiledSchema {
+    pub fields: Vec<CompiledField>,
+    /// Field index by field number for small field numbers
+    dense: Vec<Option<usize>>,
+    /// Decoders throw ERR_UNKNOWN_FIELD instead of skipping fields not in the schema
+    pub reject_unknown: bool,
+}
+
+impl CompiledSchema {
+    /// Position in `fields` of the field with this number
+    #[inline]
+    pub fn index_of(&self, field_number: u32) -> Option<usize> {
+        match self.dense.get(field_number as usize) {
+            Some(index) => *index,
+            None if field_number <= MAX_DENSE_FIELD_NUMBER => None,
+            None => self.fields.iter().position(|f| f.field_number == field_number),
+        }
+    }
+
+    /// Position of the field for a decoded tag; None means skip it, or an error when the
+    /// schema rejects unknown fields
+    pub fn decode_index(&self, field_number: u32) -> std::result::Result<Option<usize>, String> {
+        match self.index_of(field_number) {
+            None if self.reject_unknown => Err(format!(
+                "ERR_UNKNOWN_FIELD: field {} is not in the schema",
+                field_number
+            )),
+            index => Ok(index),
+        }
+    }
+}
+
+/// A field value, independent of JS representation
+pub enum Value {
+    Float(f64),
+    /// Any integer type, as its 64-bit two's-complement bit pattern
+    Int(i64),
+    Bool(bool),
+    String(String),
+    Bytes(Vec<u8>),
+    /// Field values by position in the message's `CompiledSchema::fields`
+    Message(Vec<Option<Value>>),
+    List(Vec<Value>),
+}
+
+/// Resolve a schema description, rejecting unknown types and invalid or duplicate field numbers
+/// `reject_unknown` applies to nested message schemas too
+pub fn compile(
+    descriptors: &[FieldDescriptor],
+    reject_unknown: bool,
+) -> std::result::Result<CompiledSchema, String> {
+    let mut fields: Vec<CompiledField> = Vec::with_capacity(descriptors.len());
+    for desc in descriptors {
+        let ty = FieldType::from_name(&desc.field_type)
+            .ok_or_else(|| format!("Unknown field type \"{}\" for {}", desc.field_type, desc.name))?;
+        if desc.field_number == 0 || desc.field_number > MAX_FIELD_NUMBER {
+            return Err(format!("Invalid field number {} for {}", desc.field_number, desc.name));
+        }
+        if fields.iter().any(|f| f.field_number == desc.field_number) {
+            return Err(format!("Duplicate field number {}", desc.field_number));
+        }
+
+        let message = match (ty, &desc.fields) {
+            (FieldType::Message, Some(nested)) => Some(compile(nested, reject_unknown)?),
+            (FieldType::Message, None) => {
+                return Err(format!("Message field {} has no fields", desc.name));
+            }
+            _ => None,
+        };
+        let mut enum_values: Vec<(String, i32)> = desc
+            .enum_values
+            .iter()
+            .flatten()
+            .map(|(name, &number)| (name.clone(), number))
+            .collect();
+        enum_values.sort_by_key(|&(_, number)| number);
+        let repeated = desc.repeated.unwrap_or(false);
+        let packed = repeated && ty.is_scalar() && desc.packed.unwrap_or(true);
+        let wire_type = if packed { WireType::LengthDelimited } else { ty.wire_type() };
+
+        fields.push(CompiledField {
+            name: desc.name.clone(),
+            field_number: desc.field_number,
+            ty,
+            repeated,
+            packed,
+            tag: encode_varint32(encode_tag(desc.field_number, wire_type)),
+            message,
+            enum_values,
+        });
+    }
+    let dense_len = fields
+        .iter()
+        .map(|f| f.field_number)
+        .filter(|&n| n <= MAX_DENSE_FIELD_NUMBER)
+        .max()
+        .map_or(0, |n| n as usize + 1);
+    let mut dense = vec![None; dense_len];
+    for (index, field) in fields.iter().enumerate() {
+        if let Some(slot) = dense.get_mut(field.field_number as usize) {
+            *slot = Some(index);
+        }
+    }
+
+    Ok(CompiledSchema {
+        fields,
+        dense,
+        reject_unknown,
+    })
+}
+
+/// Encode positional field values; absent fields are omitted
+pub fn encode_message(schema: &CompiledSchema, values: &[Option<Value>], out: &mut Vec<u8>) {
+    for (field, value) in schema.fields.iter().zip(values) {
+        match value {
+            None => {}
+            Some(Value::List(items)) if field.packed => {
+                if items.is_empty() {
+                    continue;
+                }
+                let mut payload = Vec::new();
+                for item in items {
+                    encode_value(field, item, &mut payload);
+                }
+                out.extend_from_slice(&field.tag);
+                out.extend_from_slice(&encode_varint32(payload.len() as u32));
+                out.extend_from_slice(&payload);
+            }
+            Some(Value::List(items)) => {
+                for item in items {
+                    out.extend_from_slice(&field.tag);
+                    encode_value(field, item, out);
+                }
+            }
+            Some(value) => {
+                out.extend_from_slice(&field.tag);
+                encode_value(field, value, out);
+            }
+        }
+    }
+}
+
+/// Encode a single value (without its tag) using the field's type
+fn encode_value(field: &CompiledField, value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Float(v) if field.ty == FieldType::Float => {
+            out.extend_from_slice(&(*v as f32).to_le_bytes());
+        }
+        Value::Float(v) => out.extend_from_slice(&v.to_le_bytes()),
+        Value::Int(v) => match field.ty {
+            FieldType::Int32 | FieldType::Enum => {
+                out.extend_from_slice(&encode_varint64(*v as i32 as i64 as u64));
+            }
+            FieldType::Uint32 => out.extend_from_slice(&encode_varint32(*v as u32)),
+            FieldType::Sint32 => out.extend_from_slice(&encode_varint32(zigzag_encode32(*v as i32))),
+            FieldType::Sint64 => out.extend_from_slice(&encode_varint64(zigzag_encode64(*v))),
+            FieldType::Fixed32 | FieldType::Sfixed32 => {
+                out.extend_from_slice(&(*v as u32).to_le_bytes());
+            }
+            FieldType::Fixed64 | FieldType::Sfixed64 => out.extend_from_slice(&v.to_le_bytes()),
+            _ => out.extend_from_slice(&encode_varint64(*v as u64)),
+        },
+        Value::Bool(v) => out.push(*v as u8),
+        Value::String(s) => {
+            out.extend_from_slice(&encode_varint32(s.len() as u32));
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Bytes(b) => {
+            out.extend_from_slice(&encode_varint32(b.len() as u32));
+            out.extend_from_slice(b);
+        }
+        Value::Message(values) => {
+            let mut nested = Vec::new();
+            if let Some(schema) = &field.message {
+                encode_message(schema, values, &mut nested);
+            }
+            out.extend_from_slice(&encode_varint32(nested.len() as u32));
+            out.extend_from_slice(&nested);
+        }
+        // Lists are expanded by encode_message and never nest
+        Value::List(_) => {}
+    }
+}
+
+/// Splice raw encoded fields (tag and value) into an encoded message by field number
+/// Each input keeps its own order; an extra field is placed before the first message field
+/// with a higher number, so ascending inputs give an ascending result
+pub fn merge_fields(message: &[u8], extra: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    let extra_fields = scan_fields(extra)?;
+    let mut pending = extra_fields.iter().peekable();
+    let mut out = Vec::with_capacity(message.len() + extra.len());
+    for field in scan_fields(message)? {
+        while let Some(next) = pending.next_if(|next| next.field_number < field.field_number) {
+            out.extend_from_slice(&extra[next.start..next.end]);
+        }
+        out.extend_from_slice(&message[field.start..field.end]);
+    }
+    for next in pending {
+        out.extend_from_slice(&extra[next.start..next.end]);
+    }
+    Ok(out)
+}
+
+/// Exact number of bytes encode_message writes for these values, without encoding them
+pub fn message_size(schema: &CompiledSchema, values: &[Option<Value>]) -> usize {
+    let mut size = 0;
+    for (field, value) in schema.fields.iter().zip(values) {
+        match value {
+            None => {}
+            Some(Value::List(items)) if field.packed => {
+                if items.is_empty() {
+                    continue;
+                }
+                let payload: usize = items.iter().map(|item| value_size(field, item)).sum();
+                size += field.tag.len() + varint_len(payload as u64) + payload;
+            }
+            Some(Value::List(items)) => {
+                let values: usize = items.iter().map(|item| value_size(field, item)).sum();
+                size += items.len() * field.tag.len() + values;
+            }
+            Some(value) => size += field.tag.len() + value_size(field, value),
+        }
+    }
+    size
+}
+
+/// Number of bytes encode_value writes for a single value
+fn value_size(field: &CompiledField, value: &Value) -> usize {
+    match value {
+        Value::Float(_) if field.ty == FieldType::Float => 4,
+        Value::Float(_) => 8,
+        Value::Int(v) => match field.ty {
+            FieldType::Int32 | FieldType::Enum => varint_len(*v as i32 as i64 as u64),
+            FieldType::Uint32 => varint_len(*v as u32 as u64),
+            FieldType::Sint32 => varint_len(zigzag_encode32(*v as i32) as u64),
+            FieldType::Sint64 => varint_len(zigzag_encode64(*v)),
+            FieldType::Fixed32 | FieldType::Sfixed32 => 4,
+            FieldType::Fixed64 | FieldType::Sfixed64 => 8,
+            _ => varint_len(*v as u64),
+        },
+        Value::Bool(_) => 1,
+        Value::String(s) => varint_len(s.len() as u64) + s.len(),
+        Value::Bytes(b) => varint_len(b.len() as u64) + b.len(),
+        Value::Message(values) => {
+            let nested = field.message.as_ref().map_or(0, |schema| message_size(schema, values));
+            varint_len(nested as u64) + nested
+        }
+        Value::List(_) => 0,
+    }
+}
+
+/// Decode a message into positional field values
+/// Unknown fields are skipped, repeated fields accept packed and unpacked encodings,
+/// and for singular fields the last occurrence wins
+pub fn decode_message(
+    schema: &CompiledSchema,
+    buf: &[u8],
+) -> std::result::Result<Vec<Option<Value>>, String> {
+    decode_message_excluding(schema, buf, &[])
+}
+
+/// Decode positional field values, skipping top-level fields whose number is in `excluded`
+/// Excluded fields are only framed, never decoded, and are skipped even by strict schemas
+pub fn decode_message_excluding(
+    schema: &CompiledSchema,
+    buf: &[u8],
+    excluded: &[u32],
+) -> std::result::Result<Vec<Option<Value>>, String> {
+    let mut values: Vec<Option<Value>> = schema.fields.iter().map(|_| None).collect();
+    for span in scan_fields(buf)? {
+        if !excluded.contains(&span.field_number) {
+            decode_span(schema, buf, &span, &mut values)?;
+        }
+    }
+    Ok(values)
+}
+
+/// Decode a scanned field into its slot; unknown fields are skipped and the last
+/// occurrence of a singular field wins
+fn decode_span(
+    schema: &CompiledSchema,
+    buf: &[u8],
+    span: &FieldSpan,
+    values: &mut [Option<Value>],
+) -> std::result::Result<(), String> {
+    let Some(index) = schema.decode_index(span.field_number)? else {
+        return Ok(());
+    };
+    let field = &schema.fields[index];
+    span_values(field, buf, span, |value| {
+        if field.repeated {
+            list_mut(&mut values[index]).push(value);
+        } else {
+            values[index] = Some(value);
+        }
+    })
+}
+
+/// Decode the value of one occurrence of `field` at `span`, passing each value to `emit`
+/// A packed payload yields one value per element
+pub(crate) fn span_values(
+    field: &CompiledField,
+    buf: &[u8],
+    span: &FieldSpan,
+    mut emit: impl FnMut(Value),
+) -> std::result::Result<(), String> {
+    let bytes = &buf[span.value_start..span.end];
+
+    if field.repeated && field.ty.is_scalar() && span.wire_type == WireType::LengthDelimited {
+        let mut pos = 0;
+        while pos < bytes.len() {
+            emit(decode_value(field, bytes, &mut pos)?);
+        }
+        return Ok(());
+    }
+    if span.wire_type != field.ty.wire_type() {
+        return Err(format!("Wire type mismatch for field {}", span.field_number));
+    }
+
+    let value = if span.wire_type == WireType::LengthDelimited {
+        decode_delimited(field, bytes)?
+    } else {
+        let mut pos = 0;
+        decode_value(field, bytes, &mut pos)?
+    };
+    emit(value);
+    Ok(())
+}
+
+/// Treat empty singular strings and bytes as absent, including in nested messages
+/// Empty elements of repeated fields are kept since their position is meaningful
+fn drop_empty(schema: &CompiledSchema, values: &mut [Option<Value>]) {
+    for (field, value) in schema.fields.iter().zip(values.iter_mut()) {
+        match value {
+            Some(Value::String(s)) if s.is_empty() => *value = None,
+            Some(Value::Bytes(b)) if b.is_empty() => *value = None,
+            Some(Value::Message(nested)) => {
+                if let Some(nested_schema) = &field.message {
+                    drop_empty(nested_schema, nested);
+                }
+            }
+            Some(Value::List(items)) => {
+                if let Some(nested_schema) = &field.message {
+                    for item in items {
+                        if let Value::Message(nested) = item {
+                            drop_empty(nested_schema, nested);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Semantic equality of two decoded messages, treating absent fields as their default
+fn message_equal(schema: &CompiledSchema, a: &[Option<Value>], b: &[Option<Value>]) -> bool {
+    schema
+        .fields
+        .iter()
+        .zip(a.iter().zip(b))
+        .all(|(field, pair)| match pair {
+            (Some(a), Some(b)) => value_equal(field, a, b),
+            (Some(v), None) | (None, Some(v)) => is_default(field, v),
+            (None, None) => true,
+        })
+}
+
+fn value_equal(field: &CompiledField, a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        // Compare bit patterns so NaN equals itself and -0.0 stays distinct from 0.0
+        (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+        (Value::Int(a), Value::Int(b)) => a == b,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        (Value::String(a), Value::String(b)) => a == b,
+        (Value::Bytes(a), Value::Bytes(b)) => a == b,
+        (Value::Message(a), Value::Message(b)) => match &field.message {
+            Some(schema) => message_equal(schema, a, b),
+            None => false,
+        },
+        (Value::List(a), Value::List(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| value_equal(field, a, b))
+        }
+        _ => false,
+    }
+}
+
+/// Whether a value is what an absent field decodes to
+fn is_default(field: &CompiledField, value: &Value) -> bool {
+    match value {
+        Value::Float(v) => v.to_bits() == 0,
+        Value::Int(v) => *v == 0,
+        Value::Bool(v) => !v,
+        Value::String(s) => s.is_empty(),
+        Value::Bytes(b) => b.is_empty(),
+        Value::Message(values) => match &field.message {
+            Some(schema) => schema
+                .fields
+                .iter()
+                .zip(values)
+                .all(|(field, v)| match v {
+                    Some(v) => is_default(field, v),
+                    None => true,
+                }),
+            None => false,
+        },
+        Value::List(items) => items.is_empty(),
+    }
+}
+
+/// The list stored for a repeated field, created on first use
+fn list_mut(slot: &mut Option<Value>) -> &mut Vec<Value> {
+    if !matches!(slot, Some(Value::List(_))) {
+        *slot = Some(Value::List(Vec::new()));
+    }
+    match slot {
+        Some(Value::List(items)) => items,
+        _ => unreachable!(),
+    }
+}
+
+/// Decode one numeric or bool value at `pos`, advancing past it
+fn decode_value(
+    field: &CompiledField,
+    buf: &[u8],
+    pos: &mut usize,
+) -> std::result::Result<Value, String> {
+    let value = match field.ty.wire_type() {
+        WireType::Varint => {
+            let raw = decode_varint64(buf, pos)?;
+            match field.ty {
+                FieldType::Bool => Value::Bool(raw != 0),
+                FieldType::Int32 | FieldType::Enum => Value::Int(raw as i32 as i64),
+                FieldType::Uint32 => Value::Int(raw as u32 as i64),
+                FieldType::Sint32 => Value::Int(zigzag_decode32(raw as u32) as i64),
+                FieldType::Sint64 => Value::Int(zigzag_decode64(raw)),
+                _ => Value::Int(raw as i64),
+            }
+        }
+        WireType::Fixed32 => {
+            let raw = fixed_bytes::<4>(buf, pos)?;
+            match field.ty {
+                FieldType::Float => Value::Float(f32::from_le_bytes(raw) as f64),
+                FieldType::Sfixed32 => Value::Int(i32::from_le_bytes(raw) as i64),
+                _ => Value::Int(u32::from_le_bytes(raw) as i64),
+            }
+        }
+        WireType::Fixed64 => {
+            let raw = fixed_bytes::<8>(buf, pos)?;
+            match field.ty {
+                FieldType::Double => Value::Float(f64::from_le_bytes(raw)),
+                _ => Value::Int(i64::from_le_bytes(raw)),
+            }
+        }
+        _ => return Err(format!("Field {} is not a numeric or bool field", field.name)),
+    };
+    Ok(value)
+}
+
+fn fixed_bytes<const N: usize>(
+    buf: &[u8],
+    pos: &mut usize,
+) -> std::result::Result<[u8; N], String> {
+    let bytes = buf
+        .get(*pos..*pos + N)
+        .ok_or_else(|| "Unexpected end of buffer".to_string())?;
+    *pos += N;
+    let mut raw = [0u8; N];
+    raw.copy_from_slice(bytes);
+    Ok(raw)
+}
+
+/// Decode a string, bytes or nested message payload
+fn decode_delimited(field: &CompiledField, bytes: &[u8]) -> std::result::Result<Value, String> {
+    match (field.ty, &field.message) {
+        (FieldType::String, _) => String::from_utf8(bytes.to_vec())
+            .map(Value::String)
+            .map_err(|_| "Invalid UTF-8 string".to_string()),
+        (FieldType::Message, Some(schema)) => decode_message(schema, bytes).map(Value::Message),
+        _ => Ok(Value::Bytes(bytes.to_vec())),
+    }
+}
+
+/// Build a JS object from positional field values
+/// Absent singular fields are left undefined and absent repeated fields become empty arrays
+pub(crate) fn message_to_js(
+    env: &Env,
+    schema: &CompiledSchema,
+    values: Vec<Option<Value>>,
+) -> Result<JsObject> {
+    let mut obj = env.create_object()?;
+    for (field, value) in schema.fields.iter().zip(values) {
+        let value = match value {
+            Some(value) => value,
+            None if field.repeated => Value::List(Vec::new()),
+            None => continue,
+        };
+        obj.set_named_property(&field.name, value_to_js(env, field, value)?)?;
+    }
+    Ok(obj)
+}
+
+pub(crate) fn value_to_js(env: &Env, field: &CompiledField, value: Value) -> Result<JsUnknown> {
+    Ok(match value {
+        Value::Float(v) => env.create_double(v)?.into_unknown(),
+        Value::Int(v) => int_to_js(env, field.ty, v)?,
+        Value::Bool(v) => env.get_boolean(v)?.into_unknown(),
+        Value::String(s) => env.create_string(&s)?.into_unknown(),
+        Value::Bytes(b) => env.create_buffer_with_data(b)?.into_raw().into_unknown(),
+        Value::Message(values) => {
+            let schema = field
+                .message
+                .as_ref()
+                .ok_or_else(|| Error::from_reason("Message field has no schema"))?;
+            message_to_js(env, schema, values)?.into_unknown()
+        }
+        Value::List(items) => {
+            let mut array = env.create_array_with_length(items.len())?;
+            for (i, item) in items.into_iter().enumerate() {
+                array.set_element(i as u32, value_to_js(env, field, item)?)?;
+            }
+            array.into_unknown()
+        }
+    })
+}
+
+/// 32-bit types become numbers; 64-bit types are numbers up to 2^53 - 1 and BigInts beyond
+fn int_to_js(env: &Env, ty: FieldType, v: i64) -> Result<JsUnknown> {
+    match ty {
+        FieldType::Uint32 | FieldType::Fixed32 => Ok(env.create_uint32(v as u32)?.into_unknown()),
+        FieldType::Int32 | FieldType::Enum | FieldType::Sint32 | FieldType::Sfixed32 => {
+            Ok(env.create_int32(v as i32)?.into_unknown())
+        }
+        FieldType::Uint64 | FieldType::Fixed64 => {
+            let u = v as u64;
+            if u <= MAX_SAFE_INTEGER {
+                Ok(env.create_double(u as f64)?.into_unknown())
+            } else {
+                env.create_bigint_from_u64(u)?.into_unknown()
+            }
+        }
+        _ => {
+            if v.unsigned_abs() <= MAX_SAFE_INTEGER {
+                Ok(env.create_int64(v)?.into_unknown())
+            } else {
+                env.create_bigint_from_i64(v)?.into_unknown()
+            }
+        }
+    }
+}
+
+/// Read every schema field from a JS object
+fn message_from_js(schema: &CompiledSchema, obj: &JsObject) -> Result<Vec<Option<Value>>> {
+    schema.fields.iter().map(|field| field_from_js(field, obj, &field.name)).collect()
+}
+
+/// Read the fields whose bit is set in a presence bitmap (bit i of byte i / 8, least
+/// significant first, for the field at position i) from a values array in schema order.
+/// Fields past the end of the bitmap are absent; a present field must have a value
+pub(crate) fn present_values_from_js(
+    schema: &CompiledSchema,
+    presence_bits: &[u8],
+    values: &JsObject,
+) -> Result<Vec<Option<Value>>> {
+    let mut present = Vec::with_capacity(schema.fields.len());
+    for (index, field) in schema.fields.iter().enumerate() {
+        let bit = presence_bits.get(index / 8).map_or(0, |byte| (byte >> (index % 8)) & 1);
+        if bit == 0 {
+            present.push(None);
+            continue;
+        }
+        let value = field_from_js(field, values, &index.to_string())?;
+        if value.is_none() {
+            return Err(Error::from_reason(format!(
+                "Field {} is marked present but has no value at index {}",
+                field.name, index
+            )));
+        }
+        present.push(value);
+    }
+    Ok(present)
+}
+
+/// Read one field stored under `key`
+fn field_from_js(field: &CompiledField, obj: &JsObject, key: &str) -> Result<Option<Value>> {
+    match field.ty {
+        FieldType::Double | FieldType::Float => {
+            get_field(obj, field, key, |v: f64| Ok(Value::Float(v)))
+        }
+        FieldType::Bool => get_field(obj, field, key, |v: bool| Ok(Value::Bool(v))),
+        FieldType::String => get_field(obj, field, key, |v: String| Ok(Value::String(v))),
+        FieldType::Bytes => get_field(obj, field, key, |v: Buffer| Ok(Value::Bytes(v.to_vec()))),
+        FieldType::Message => {
+            let schema = field
+                .message
+                .as_ref()
+                .ok_or_else(|| Error::from_reason("Message field has no schema"))?;
+            get_field(obj, field, key, |v: JsObject| {
+                Ok(Value::Message(message_from_js(schema, &v)?))
+            })
+        }
+        _ => get_field(obj, field, key, |v: Either<f64, BigInt>| {
+            Ok(Value::Int(match v {
+                Either::A(n) => number_bits(field, n)?,
+                Either::B(b) => bigint_bits(&b),
+            }))
+        }),
+    }
+}
+
+/// Read a field (or each element of a repeated field) as `V`; null and undefined are absent
+fn get_field<V: FromNapiValue>(
+    obj: &JsObject,
+    field: &CompiledField,
+    key: &str,
+    convert: impl Fn(V) -> Result<Value>,
+) -> Result<Option<Value>> {
+    if field.repeated {
+        let items = obj.get::<_, Option<Vec<V>>>(key)?.flatten();
+        items
+            .map(|items| Ok(Value::List(items.into_iter().map(&convert).collect::<Result<_>>()?)))
+            .transpose()
+    } else {
+        obj.get::<_, Option<V>>(key)?.flatten().map(convert).transpose()
+    }
+}
+
+/// Bit pattern of a JS number used as a 64-bit integer (unsigned above i64::MAX)
+/// Fractional, non-finite and out-of-range numbers for the field's type throw ERR_RANGE
+fn number_bits(field: &CompiledField, n: f64) -> Result<i64> {
+    let range = match field.ty {
+        FieldType::Uint32 | FieldType::Fixed32 => U32_RANGE,
+        FieldType::Uint64 | FieldType::Fixed64 => U64_RANGE,
+        FieldType::Int64 | FieldType::Sint64 | FieldType::Sfixed64 => I64_RANGE,
+        _ => I32_RANGE,
+    };
+    let n = checked_integer(&field.name, n, range)?;
+    Ok(if n < 0.0 { n as i64 } else { n as u64 as i64 })
+}
+
+/// Low 64 bits of a BigInt in two's complement
+fn bigint_bits(value: &BigInt) -> i64 {
+    let low = value.words.first().copied().unwrap_or(0);
+    if value.sign_bit {
+        low.wrapping_neg() as i64
+    } else {
+        low as i64
+    }
+}
+
+/// Precompiled schema reused across encode_with and decode_with calls
+#[napi]
+pub struct SchemaHandle {
+    pub(crate) schema: CompiledSchema,
+}
+
+/// Compile a schema description once for repeated encoding and decoding
+/// With `reject_unknown_fields` set, decoding throws ERR_UNKNOWN_FIELD for any field number
+/// not in the schema (at any nesting level) instead of skipping it
+#[napi]
+pub fn compile_schema(
+    schema: Vec<FieldDescriptor>,
+    reject_unknown_fields: Option<bool>,
+) -> Result<SchemaHandle> {
+    let schema = compile(&schema, reject_unknown_fields.unwrap_or(false))
+        .map_err(Error::from_reason)?;
+    Ok(SchemaHandle { schema })
+}
+
+/// Encode a JS object using a compiled schema
+/// Missing, null and undefined properties are omitted; 64-bit fields accept numbers or BigInts.
+/// Numbers that are fractional, non-finite or out of range for an integer field throw ERR_RANGE.
+/// Empty strings and bytes are written as present (length 0) unless `skip_empty` is set, which
+/// omits them as proto3 does for fields without explicit presence. `unknowns` holds raw fields
+/// (tags and values) preserved from an earlier decode; each is spliced in before the first
+/// known field with a higher number, so unknown fields survive a decode/modify/encode cycle
+#[napi]
+pub fn encode_with(
+    handle: &SchemaHandle,
+    obj: JsObject,
+    skip_empty: Option<bool>,
+    unknowns: Option<Buffer>,
+) -> Result<Buffer> {
+    let mut values = message_from_js(&handle.schema, &obj)?;
+    if skip_empty.unwrap_or(false) {
+        drop_empty(&handle.schema, &mut values);
+    }
+    let mut out = Vec::new();
+    encode_message(&handle.schema, &values, &mut out);
+    if let Some(unknowns) = unknowns {
+        out = merge_fields(&out, unknowns.as_ref()).map_err(Error::from_reason)?;
+    }
+    Ok(Buffer::from(out))
+}
+
+/// Exact size in bytes of encodeWith(handle, obj, skipEmpty) without encoding, including
+/// nested messages' tags and length prefixes
+#[napi]
+pub fn encoded_size(handle: &SchemaHandle, obj: JsObject, skip_empty: Option<bool>) -> Result<u32> {
+    let mut values = message_from_js(&handle.schema, &obj)?;
+    if skip_empty.unwrap_or(false) {
+        drop_empty(&handle.schema, &mut values);
+    }
+    Ok(message_size(&handle.schema, &values) as u32)
+}
+
+/// Compare two encoded messages field by field after decoding both with a compiled schema
+/// Field order, non-minimal varints, packed vs unpacked repeated fields and omitted defaults
+/// (0, false, "", empty) do not make messages unequal; unknown fields are ignored
+#[napi]
+pub fn messages_equal(a: Buffer, b: Buffer, handle: &SchemaHandle) -> Result<bool> {
+    let schema = &handle.schema;
+    let a = decode_message(schema, a.as_ref()).map_err(Error::from_reason)?;
+    let b = decode_message(schema, b.as_ref()).map_err(Error::from_reason)?;
+    Ok(message_equal(schema, &a, &b))
+}
+
+/// Decode a buffer into a JS object using a compiled schema
+/// Unknown fields are skipped; 64-bit values above 2^53 - 1 are returned as BigInts
+#[napi]
+pub fn decode_with(env: Env, handle: &SchemaHandle, buffer: Buffer) -> Result<JsObject> {
+    let values = decode_message(&handle.schema, buffer.as_ref()).map_err(Error::from_reason)?;
+    message_to_js(&env, &handle.schema, values)
+}
+
+/// Decode the message in the `length` bytes at `offset` (by default everything after
+/// `offset`), returning `{ value, bytesConsumed }` so the caller can advance its cursor.
+/// Protobuf messages are not self-delimiting: concatenated messages parse as a single merged
+/// message, so the end must come from the caller, e.g. a length prefix or a known size
+#[napi]
+pub fn decode_with_length(
+    env: Env,
+    handle: &SchemaHandle,
+    buffer: Buffer,
+    offset: Option<u32>,
+    length: Option<u32>,
+) -> Result<JsObject> {
+    let buf = buffer.as_ref();
+    let offset = offset.unwrap_or(0) as usize;
+    if offset > buf.len() {
+        return Err(Error::from_reason("Offset out of bounds"));
+    }
+    let end = length.map_or(buf.len(), |length| offset + length as usize);
+    if end > buf.len() {
+        return Err(Error::from_reason(format!(
+            "Length {} at offset {} exceeds buffer length {}",
+            end - offset,
+            offset,
+            buf.len()
+        )));
+    }
+    let values =
+        decode_message(&handle.schema, &buf[offset..end]).map_err(Error::from_reason)?;
+    let mut result = env.create_object()?;
+    result.set_named_property("value", message_to_js(&env, &handle.schema, values)?)?;
+    result.set_named_property("bytesConsumed", env.create_uint32((end - offset) as u32)?)?;
+    Ok(result)
+}
+
+/// Decode only the singular numeric and bool fields of a message into an existing object
+/// String, bytes, message and repeated fields are skipped, so no JS strings, buffers or
+/// arrays are allocated. Absent fields are reset to 0 (or false) so values never leak
+/// from a previous message decoded into the same target
+#[napi]
+pub fn decode_scalars_into(
+    env: Env,
+    handle: &SchemaHandle,
+    buffer: Buffer,
+    mut target: JsObject,
+) -> Result<()> {
+    let schema = &handle.schema;
+    let buf = buffer.as_ref();
+    let is_target = |field: &CompiledField| !field.repeated && field.ty.is_scalar();
+
+    let mut values: Vec<Option<Value>> = schema.fields.iter().map(|_| None).collect();
+    let mut pos = 0;
+    while pos < buf.len() {
+        let span = scan_field(buf, pos).map_err(Error::from_reason)?;
+        pos = span.end;
+        let Some(index) = schema
+            .decode_index(span.field_number)
+            .map_err(Error::from_reason)?
+        else {
+            continue;
+        };
+        let field = &schema.fields[index];
+        if !is_target(field) {
+            continue;
+        }
+        if span.wire_type != field.ty.wire_type() {
+            return Err(Error::from_reason(format!(
+                "Wire type mismatch for field {}",
+                span.field_number
+            )));
+        }
+        let mut value_pos = span.value_start;
+        values[index] =
+            Some(decode_value(field, buf, &mut value_pos).map_err(Error::from_reason)?);
+    }
+
+    for (field, value) in schema.fields.iter().zip(values) {
+        if !is_target(field) {
+            continue;
+        }
+        let value = value.unwrap_or(match field.ty {
+            FieldType::Bool => Value::Bool(false),
+            FieldType::Double | FieldType::Float => Value::Float(0.0),
+            _ => Value::Int(0),
+        });
+        target.set_named_property(&field.name, value_to_js(&env, field, value)?)?;
+    }
+    Ok(())
+}
+
+/// Decode a flat message of scalar fields numbered 1 to 15, whose tags are all one byte
+/// Each tag byte is looked up directly and matching values are decoded in place without
+/// scanning the field first. Throws if the schema has string, bytes or message fields, or
+/// if a multi-byte tag appears (use decodeWith for those messages)
+#[napi]
+pub fn decode_flat_small(env: Env, handle: &SchemaHandle, buffer: Buffer) -> Result<JsObject> {
+    let schema = &handle.schema;
+    if let Some(field) = schema.fields.iter().find(|f| !f.ty.is_scalar()) {
+        return Err(Error::from_reason(format!(
+            "decodeFlatSmall only supports scalar fields; {} is not scalar",
+            field.name
+        )));
+    }
+
+    let buf = buffer.as_ref();
+    let mut values: Vec<Option<Value>> = schema.fields.iter().map(|_| None).collect();
+    let mut pos = 0;
+    while pos < buf.len() {
+        let tag = buf[pos];
+        if tag >= 0x80 {
+            return Err(Error::from_reason(format!(
+                "decodeFlatSmall requires single-byte tags; multi-byte tag at offset {}",
+                pos
+            )));
+        }
+        let index = schema
+            .decode_index((tag >> 3) as u32)
+            .map_err(Error::from_reason)?;
+        let wire_type = WireType::from_u8(tag & 7);
+        match index {
+            Some(index) if wire_type == Some(schema.fields[index].ty.wire_type()) => {
+                let field = &schema.fields[index];
+                pos += 1;
+                let value = decode_value(field, buf, &mut pos).map_err(Error::from_reason)?;
+                if field.repeated {
+                    list_mut(&mut values[index]).push(value);
+                } else {
+                    values[index] = Some(value);
+                }
+            }
+            // Packed fields, wire type mismatches and unknown fields take the general path
+            _ => {
+                let span = scan_field(buf, pos).map_err(Error::from_reason)?;
+                pos = span.end;
+                decode_span(schema, buf, &span, &mut values).map_err(Error::from_reason)?;
+            }
+        }
+    }
+    message_to_js(&env, schema, values)
+}
@@ -0,0 +1,38 @@
+//! Destinations a Writer can stream finished bytes to instead of holding the whole message
+
+use napi::bindgen_prelude::*;
+use napi::{Env, JsFunction, Ref};
+
+/// Receives chunks of encoded output in order
+/// Implementations only ever see bytes that will not be rewritten (no open fork regions)
+pub(crate) trait ByteSink {
+    fn write(&mut self, bytes: &[u8]) -> Result<()>;
+}
+
+/// Forwards each chunk to a JS callback as a Buffer
+pub(crate) struct JsCallbackSink {
+    env: Env,
+    callback: Ref<()>,
+}
+
+impl JsCallbackSink {
+    pub(crate) fn new(env: Env, callback: JsFunction) -> Result<Self> {
+        let callback = env.create_reference(callback)?;
+        Ok(JsCallbackSink { env, callback })
+    }
+}
+
+impl ByteSink for JsCallbackSink {
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        let callback: JsFunction = self.env.get_reference_value(&self.callback)?;
+        let chunk = self.env.create_buffer_with_data(bytes.to_vec())?.into_raw();
+        callback.call(None, &[chunk])?;
+        Ok(())
+    }
+}
+
+impl Drop for JsCallbackSink {
+    fn drop(&mut self) {
+        let _ = self.callback.unref(self.env);
+    }
+}
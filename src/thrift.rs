@@ -0,0 +1,137 @@
+//! Thrift compact protocol primitives built on the shared varint and zigzag core
+//!
+//! Compact protocol integers are zigzag varints exactly like protobuf's sint32/sint64,
+//! but field headers pack a field-id delta and a type nibble into a single byte.
+
+use crate::varint::{decode_varint64, encode_varint64, zigzag_decode64, zigzag_encode64};
+use crate::VarintResult;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// Compact type id marking the end of a struct
+pub const COMPACT_STOP: u8 = 0;
+
+/// Largest compact type id (STRUCT)
+const COMPACT_MAX_TYPE: u8 = 12;
+
+/// Encode a zigzag value as a compact-protocol varint
+#[inline]
+pub fn write_compact_varint(value: i64) -> Vec<u8> {
+    encode_varint64(zigzag_encode64(value))
+}
+
+/// Decode a compact-protocol zigzag varint starting at offset
+#[inline]
+pub fn read_compact_varint(buffer: &[u8], offset: &mut usize) -> std::result::Result<i64, String> {
+    decode_varint64(buffer, offset).map(zigzag_decode64)
+}
+
+/// Encode a compact field header
+/// Uses the short form (delta << 4 | type) when the id is 1-15 above the previous field,
+/// otherwise the long form (type byte followed by a zigzag varint field id)
+pub fn encode_field_header(field_id: i16, last_field_id: i16, field_type: u8) -> Vec<u8> {
+    let delta = field_id as i32 - last_field_id as i32;
+    if (1..=15).contains(&delta) {
+        return vec![((delta as u8) << 4) | field_type];
+    }
+    let mut result = vec![field_type];
+    result.extend_from_slice(&write_compact_varint(field_id as i64));
+    result
+}
+
+/// Decode a compact field header, returning (field_id, field_type)
+/// A STOP header decodes as field id 0 with type COMPACT_STOP
+pub fn decode_field_header(
+    buffer: &[u8],
+    offset: &mut usize,
+    last_field_id: i16,
+) -> std::result::Result<(i16, u8), String> {
+    if *offset >= buffer.len() {
+        return Err("Unexpected end of buffer".to_string());
+    }
+    let byte = buffer[*offset];
+    *offset += 1;
+
+    let field_type = byte & 0x0F;
+    if field_type > COMPACT_MAX_TYPE {
+        return Err("Invalid compact type".to_string());
+    }
+    if field_type == COMPACT_STOP {
+        return Ok((0, COMPACT_STOP));
+    }
+
+    let delta = byte >> 4;
+    // Range-check the full value, since narrowing first would wrap large ids into range
+    let field_id = if delta != 0 {
+        last_field_id as i64 + delta as i64
+    } else {
+        read_compact_varint(buffer, offset)?
+    };
+    let field_id = i16::try_from(field_id)
+        .map_err(|_| format!("ERR_RANGE: field id {} is out of range", field_id))?;
+
+    Ok((field_id, field_type))
+}
+
+/// Decoded Thrift compact field header
+#[napi(object)]
+pub struct ThriftFieldHeader {
+    pub field_id: i32,
+    pub field_type: u32,
+    pub length: u32,
+}
+
+/// Encode a signed integer as a Thrift compact zigzag varint
+#[napi]
+pub fn thrift_encode_varint(value: i64) -> Buffer {
+    Buffer::from(write_compact_varint(value))
+}
+
+/// Decode a Thrift compact zigzag varint from a buffer at the given position
+#[napi]
+pub fn thrift_decode_varint(buffer: Buffer, pos: u32) -> Result<VarintResult> {
+    let mut offset = pos as usize;
+    let value = read_compact_varint(buffer.as_ref(), &mut offset)
+        .map_err(Error::from_reason)?;
+    Ok(VarintResult {
+        value,
+        length: offset as u32 - pos,
+    })
+}
+
+/// Encode a Thrift compact field header relative to the previous field id
+#[napi]
+pub fn thrift_encode_field_header(
+    field_id: i32,
+    last_field_id: i32,
+    field_type: u32,
+) -> Result<Buffer> {
+    let field_id =
+        i16::try_from(field_id).map_err(|_| Error::from_reason("Field id out of range"))?;
+    let last_field_id =
+        i16::try_from(last_field_id).map_err(|_| Error::from_reason("Field id out of range"))?;
+    if field_type == COMPACT_STOP as u32 || field_type > COMPACT_MAX_TYPE as u32 {
+        return Err(Error::from_reason("Invalid compact type"));
+    }
+    Ok(Buffer::from(encode_field_header(field_id, last_field_id, field_type as u8)))
+}
+
+/// Decode a Thrift compact field header from a buffer at the given position
+/// A STOP header returns field id 0 and field type 0
+#[napi]
+pub fn thrift_decode_field_header(
+    buffer: Buffer,
+    pos: u32,
+    last_field_id: i32,
+) -> Result<ThriftFieldHeader> {
+    let last_field_id =
+        i16::try_from(last_field_id).map_err(|_| Error::from_reason("Field id out of range"))?;
+    let mut offset = pos as usize;
+    let (field_id, field_type) = decode_field_header(buffer.as_ref(), &mut offset, last_field_id)
+        .map_err(Error::from_reason)?;
+    Ok(ThriftFieldHeader {
+        field_id: field_id as i32,
+        field_type: field_type as u32,
+        length: offset as u32 - pos,
+    })
+}
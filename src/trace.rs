@@ -0,0 +1,100 @@
+//! Human-readable tracing of Writer operations
+
+use std::fmt::Display;
+
+/// A single recorded Writer operation
+#[derive(Debug, Clone)]
+pub enum TraceEntry {
+    /// A field tag; the value or nested message that follows belongs to this field
+    Tag { field_number: u32 },
+    /// A typed value write, labelled with the method that produced it
+    Value { kind: &'static str, text: String },
+    /// Start of a nested message (fork)
+    Open,
+    /// End of a nested message (ldelim)
+    Close,
+}
+
+/// Quote a string the way protobuf text format does, octal-escaping its non-printable bytes
+pub fn quote_string(value: &str) -> String {
+    quote_bytes(value.as_bytes())
+}
+
+/// Format a float the way protobuf text format does (nan, inf, -inf)
+pub fn format_float(value: f64) -> String {
+    if value.is_nan() {
+        "nan".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format packed values as a text-format list, e.g. `[1, 2, 3]`
+pub fn format_list<T: Display>(values: &[T]) -> String {
+    let items: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// Quote raw bytes as a text-format string, octal-escaping non-printable bytes
+pub fn quote_bytes(value: &[u8]) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for &b in value {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            0x20..=0x7e => out.push(b as char),
+            _ => out.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Render recorded operations as protobuf text format
+/// A tagged value becomes `field_number: value` and a tagged fork a `field_number { ... }`
+/// block. Writes with no tag before them (such as a tag written with uint32()) cannot be
+/// attributed to a field, so they are rendered as `# kind: value` comments
+pub fn render_text_format(entries: &[TraceEntry]) -> String {
+    let mut out = String::new();
+    let mut pending: Option<u32> = None;
+    let mut open_tagged: Vec<bool> = Vec::new(); // Whether each open fork followed a tag
+    for entry in entries {
+        let indent = "  ".repeat(open_tagged.len());
+        match entry {
+            TraceEntry::Tag { field_number } => {
+                if let Some(orphan) = pending.replace(*field_number) {
+                    out.push_str(&format!("{}# {}: no value\n", indent, orphan));
+                }
+            }
+            TraceEntry::Value { kind, text } => match pending.take() {
+                Some(field_number) => {
+                    out.push_str(&format!("{}{}: {}\n", indent, field_number, text))
+                }
+                None => out.push_str(&format!("{}# {}: {}\n", indent, kind, text)),
+            },
+            TraceEntry::Open => {
+                let field_number = pending.take();
+                match field_number {
+                    Some(field_number) => out.push_str(&format!("{}{} {{\n", indent, field_number)),
+                    None => out.push_str(&format!("{}# {{\n", indent)),
+                }
+                open_tagged.push(field_number.is_some());
+            }
+            TraceEntry::Close => {
+                if let Some(orphan) = pending.take() {
+                    out.push_str(&format!("{}# {}: no value\n", indent, orphan));
+                }
+                let tagged = open_tagged.pop().unwrap_or(true);
+                let indent = "  ".repeat(open_tagged.len());
+                out.push_str(&format!("{}{}}}\n", indent, if tagged { "" } else { "# " }));
+            }
+        }
+    }
+    if let Some(orphan) = pending {
+        out.push_str(&format!("{}# {}: no value\n", "  ".repeat(open_tagged.len()), orphan));
+    }
+    out
+}
@@ -0,0 +1,352 @@
+use crate::hash::HashAlgorithm;
+use crate::scan::{scan_fields, scan_group, FieldSpan, MAX_GROUP_DEPTH};
+use crate::varint::{decode_varint64, encode_varint32, encode_varint64};
+use crate::wire_type::{encode_tag, WireType};
+use napi::bindgen_prelude::*;
+use napi::{Env, JsFunction, JsObject, NapiRaw};
+use napi_derive::napi;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+
+/// Maximum nesting re-encoded before sub-message payloads are treated as opaque bytes,
+/// whatever depth the caller asks for
+const MAX_CANONICAL_DEPTH: u32 = 32;
+
+/// Whether a wire type can appear as an element of a packed repeated field
+#[inline]
+fn is_packable(wire_type: WireType) -> bool {
+    matches!(wire_type, WireType::Varint | WireType::Fixed32 | WireType::Fixed64)
+}
+
+/// Rewrite a repeated numeric field between packed and unpacked encodings
+/// All other fields are copied through unchanged. When unpacking, `element_wire_type`
+/// selects how the packed payload is split into elements (varint by default)
+#[napi]
+pub fn repack_field(
+    buffer: Buffer,
+    field_number: u32,
+    to_packed: bool,
+    element_wire_type: Option<u32>,
+) -> Result<Buffer> {
+    let buf = buffer.as_ref();
+    let fields = scan_fields(buf).map_err(Error::from_reason)?;
+
+    let out = if to_packed {
+        pack_field(buf, &fields, field_number)?
+    } else {
+        let element_type = match element_wire_type {
+            Some(wt) => u8::try_from(wt)
+                .ok()
+                .and_then(WireType::from_u8)
+                .filter(|element_type| is_packable(*element_type))
+                .ok_or_else(|| Error::from_reason("Invalid element wire type"))?,
+            None => WireType::Varint,
+        };
+        unpack_field(buf, &fields, field_number, element_type)?
+    };
+
+    Ok(Buffer::from(out))
+}
+
+/// Merge every occurrence of `field_number` into one packed field in the original element
+/// order. Unpacked elements and the payloads of already-packed occurrences are joined into
+/// the packed field, which takes the place of the first occurrence
+fn pack_field(buf: &[u8], fields: &[FieldSpan], field_number: u32) -> Result<Vec<u8>> {
+    let is_element = |f: &FieldSpan| {
+        f.field_number == field_number
+            && (is_packable(f.wire_type) || f.wire_type == WireType::LengthDelimited)
+    };
+
+    let mut element_type = None;
+    for field in fields.iter().filter(|f| is_element(f) && is_packable(f.wire_type)) {
+        if *element_type.get_or_insert(field.wire_type) != field.wire_type {
+            return Err(Error::from_reason("Mixed wire types in repeated field"));
+        }
+    }
+    // Nothing unpacked to merge, so the message is left as it is
+    let Some(element_type) = element_type else {
+        return Ok(buf.to_vec());
+    };
+
+    let mut payload = Vec::new();
+    for field in fields.iter().filter(|f| is_element(f)) {
+        let value = &buf[field.value_start..field.end];
+        if field.wire_type == WireType::LengthDelimited {
+            check_packed_payload(value, element_type)?;
+        }
+        payload.extend_from_slice(value);
+    }
+
+    let mut out = Vec::with_capacity(buf.len());
+    let mut emitted = false;
+    for field in fields {
+        if !is_element(field) {
+            out.extend_from_slice(&buf[field.start..field.end]);
+        } else if !emitted {
+            out.extend_from_slice(&encode_varint32(encode_tag(field_number, WireType::LengthDelimited)));
+            out.extend_from_slice(&encode_varint32(payload.len() as u32));
+            out.extend_from_slice(&payload);
+            emitted = true;
+        }
+    }
+
+    Ok(out)
+}
+
+/// Fail unless `payload` splits exactly into elements of `element_type`
+fn check_packed_payload(payload: &[u8], element_type: WireType) -> Result<()> {
+    let fits = match element_type {
+        WireType::Varint => {
+            let mut pos = 0;
+            while pos < payload.len() {
+                decode_varint64(payload, &mut pos).map_err(Error::from_reason)?;
+            }
+            true
+        }
+        WireType::Fixed32 => payload.len().is_multiple_of(4),
+        _ => payload.len().is_multiple_of(8),
+    };
+    if !fits {
+        return Err(Error::from_reason("Packed field length does not match element size"));
+    }
+    Ok(())
+}
+
+/// Split every packed occurrence of `field_number` into individually tagged elements
+fn unpack_field(
+    buf: &[u8],
+    fields: &[FieldSpan],
+    field_number: u32,
+    element_type: WireType,
+) -> Result<Vec<u8>> {
+    let tag = encode_varint32(encode_tag(field_number, element_type));
+    let mut out = Vec::with_capacity(buf.len());
+
+    for field in fields {
+        if field.field_number != field_number || field.wire_type != WireType::LengthDelimited {
+            out.extend_from_slice(&buf[field.start..field.end]);
+            continue;
+        }
+
+        let payload = &buf[field.value_start..field.end];
+        let mut pos = 0;
+        while pos < payload.len() {
+            let start = pos;
+            match element_type {
+                WireType::Varint => {
+                    decode_varint64(payload, &mut pos).map_err(Error::from_reason)?;
+                }
+                WireType::Fixed32 => pos += 4,
+                _ => pos += 8,
+            }
+            if pos > payload.len() {
+                return Err(Error::from_reason("Packed field length does not match element size"));
+            }
+            out.extend_from_slice(&tag);
+            out.extend_from_slice(&payload[start..pos]);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Re-encode a message canonically: minimal varints, fields stably sorted by field number,
+/// and, down to `depth` levels, length-delimited payloads that parse as messages
+/// canonicalized recursively
+pub fn canonicalize(buf: &[u8], depth: u32) -> std::result::Result<Vec<u8>, String> {
+    reencode_at_depth(buf, depth.min(MAX_CANONICAL_DEPTH), true)
+}
+
+/// Re-encode with minimal varints, treating length-delimited payloads that parse as messages
+/// as nested messages for `depth` more levels; deeper payloads are copied verbatim.
+/// Fields are stably sorted by field number when `sort` is set, otherwise kept in order
+fn reencode_at_depth(buf: &[u8], depth: u32, sort: bool) -> std::result::Result<Vec<u8>, String> {
+    let mut fields = scan_fields(buf)?;
+    if sort {
+        fields.sort_by_key(|f| f.field_number);
+    }
+
+    let mut out = Vec::with_capacity(buf.len());
+    for field in &fields {
+        out.extend_from_slice(&encode_varint32(encode_tag(field.field_number, field.wire_type)));
+        let value = &buf[field.value_start..field.end];
+        match field.wire_type {
+            WireType::Varint => {
+                let mut pos = 0;
+                out.extend_from_slice(&encode_varint64(decode_varint64(value, &mut pos)?));
+            }
+            WireType::LengthDelimited => {
+                let nested = if depth > 0 && !value.is_empty() {
+                    reencode_at_depth(value, depth - 1, sort).ok()
+                } else {
+                    None
+                };
+                let payload = nested.as_deref().unwrap_or(value);
+                out.extend_from_slice(&encode_varint32(payload.len() as u32));
+                out.extend_from_slice(payload);
+            }
+            _ => out.extend_from_slice(value),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Re-encode a message canonically so logically equal messages have identical bytes
+/// Varints are minimized and fields are stably sorted by field number (repeated values keep
+/// their order). Length-delimited payloads are copied verbatim unless `depth` (default 0) is
+/// given: then payloads that parse as messages are canonicalized too, down to that many
+/// levels. Without a schema a string or bytes value can look like a message, so only
+/// recurse when such values cannot occur at those levels
+#[napi]
+pub fn canonicalize_message(buffer: Buffer, depth: Option<u32>) -> Result<Buffer> {
+    let out = canonicalize(buffer.as_ref(), depth.unwrap_or(0))
+        .map_err(Error::from_reason)?;
+    Ok(Buffer::from(out))
+}
+
+/// Re-encode every varint in a message minimally (tags, length prefixes and varint values)
+/// without reordering anything, unlike canonicalizeMessage. Length-delimited payloads are
+/// copied verbatim unless `depth` (default 0) is given: then payloads that parse as messages
+/// are minimized too, down to that many levels. Without a schema a string or bytes value can
+/// look like a message, so only recurse when such values cannot occur at those levels
+#[napi]
+pub fn minimize_varints(buffer: Buffer, depth: Option<u32>) -> Result<Buffer> {
+    let depth = depth.unwrap_or(0).min(MAX_CANONICAL_DEPTH);
+    let out = reencode_at_depth(buffer.as_ref(), depth, false).map_err(Error::from_reason)?;
+    Ok(Buffer::from(out))
+}
+
+/// Hash a message independently of field order and varint encoding
+/// `algorithm` is "sha256" (default) or "fnv1a64". The message is canonicalized first, with
+/// `depth` passed to canonicalizeMessage, so nested messages only hash independently of
+/// their encoding when `depth` covers them
+#[napi]
+pub fn message_hash(
+    buffer: Buffer,
+    algorithm: Option<String>,
+    depth: Option<u32>,
+) -> Result<Buffer> {
+    let algorithm = match algorithm.as_deref() {
+        Some(name) => HashAlgorithm::from_name(name)
+            .ok_or_else(|| Error::from_reason("Unknown hash algorithm"))?,
+        None => HashAlgorithm::Sha256,
+    };
+    let canonical =
+        canonicalize(buffer.as_ref(), depth.unwrap_or(0)).map_err(Error::from_reason)?;
+    Ok(Buffer::from(algorithm.digest(&canonical)))
+}
+
+/// A top-level field that differs between two messages
+#[napi(object)]
+pub struct FieldDiff {
+    pub field_number: u32,
+    /// Whether the field occurs in the first message
+    pub in_a: bool,
+    /// Whether the field occurs in the second message
+    pub in_b: bool,
+}
+
+/// Concatenated encodings (tag and value) of every occurrence of each field, by field number
+fn field_bytes(buf: &[u8]) -> std::result::Result<BTreeMap<u32, Vec<u8>>, String> {
+    let mut by_number: BTreeMap<u32, Vec<u8>> = BTreeMap::new();
+    for field in scan_fields(buf)? {
+        by_number
+            .entry(field.field_number)
+            .or_default()
+            .extend_from_slice(&buf[field.start..field.end]);
+    }
+    Ok(by_number)
+}
+
+/// Report the top-level fields whose presence or raw encoding differs between two messages
+/// Repeated fields are compared by all their occurrences in order. Results are sorted by
+/// field number; fields present in both with different bytes have `inA` and `inB` set
+#[napi]
+pub fn diff_messages(a: Buffer, b: Buffer) -> Result<Vec<FieldDiff>> {
+    let fields_a = field_bytes(a.as_ref()).map_err(Error::from_reason)?;
+    let fields_b = field_bytes(b.as_ref()).map_err(Error::from_reason)?;
+
+    let mut numbers: Vec<u32> = fields_a.keys().chain(fields_b.keys()).copied().collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+
+    Ok(numbers
+        .into_iter()
+        .filter(|n| fields_a.get(n) != fields_b.get(n))
+        .map(|n| FieldDiff {
+            field_number: n,
+            in_a: fields_a.contains_key(&n),
+            in_b: fields_b.contains_key(&n),
+        })
+        .collect())
+}
+
+/// Re-emit a message with selected top-level fields rewritten by JS callbacks, keyed by field
+/// number in `transforms`. Each callback receives a field's value bytes (without tag or
+/// length prefix) and returns replacement value bytes, or null to drop the field; the tag,
+/// and the length prefix for length-delimited fields, are written natively. Fields without
+/// a callback are copied byte for byte. Callbacks run once per occurrence, in order. A group's
+/// value is its body followed by the matching EndGroup tag, and a replacement must end the same way
+#[napi]
+pub fn transform_message(env: Env, buffer: Buffer, transforms: JsObject) -> Result<Buffer> {
+    let buf = buffer.as_ref();
+    let fields = scan_fields(buf).map_err(Error::from_reason)?;
+
+    let mut callbacks: HashMap<u32, Option<JsFunction>> = HashMap::new();
+    let mut out = Vec::with_capacity(buf.len());
+    for field in &fields {
+        let callback = match callbacks.entry(field.field_number) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(transforms.get(field.field_number.to_string())?),
+        };
+        let Some(callback) = callback else {
+            out.extend_from_slice(&buf[field.start..field.end]);
+            continue;
+        };
+
+        let value = env.create_buffer_with_data(buf[field.value_start..field.end].to_vec())?;
+        let result = callback.call(None, &[value.into_raw()])?;
+        if matches!(result.get_type()?, ValueType::Null | ValueType::Undefined) {
+            continue;
+        }
+        let replacement = unsafe { Buffer::from_napi_value(env.raw(), result.raw())? };
+        let bytes = replacement.as_ref();
+        check_value_bytes(field, bytes)?;
+
+        out.extend_from_slice(&encode_varint32(encode_tag(field.field_number, field.wire_type)));
+        if field.wire_type == WireType::LengthDelimited {
+            out.extend_from_slice(&encode_varint32(bytes.len() as u32));
+        }
+        out.extend_from_slice(bytes);
+    }
+
+    Ok(Buffer::from(out))
+}
+
+/// Check that replacement value bytes form exactly one value of the field's wire type
+fn check_value_bytes(field: &FieldSpan, bytes: &[u8]) -> Result<()> {
+    let valid = match field.wire_type {
+        WireType::Varint => {
+            let mut pos = 0;
+            decode_varint64(bytes, &mut pos).is_ok() && pos == bytes.len()
+        }
+        WireType::Fixed32 => bytes.len() == 4,
+        WireType::Fixed64 => bytes.len() == 8,
+        // A group's value is its body and the matching EndGroup tag, which must end the bytes
+        WireType::StartGroup => {
+            scan_group(bytes, 0, Some(field.field_number), 1, MAX_GROUP_DEPTH)
+                .is_ok_and(|(_, end)| end == bytes.len())
+        }
+        _ => true,
+    };
+    if !valid {
+        return Err(Error::from_reason(format!(
+            "Transform for field {} returned {} bytes that are not a single {:?} value",
+            field.field_number,
+            bytes.len(),
+            field.wire_type
+        )));
+    }
+    Ok(())
+}
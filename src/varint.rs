@@ -1,4 +1,9 @@
-/// Varint encoding and decoding utilities
+//! Varint encoding and decoding utilities
+
+/// The buffer ended part-way through a varint; more input may complete it
+pub const ERR_NEED_MORE_DATA: &str = "ERR_NEED_MORE_DATA: Unexpected end of buffer";
+/// The varint has more continuation bytes than its type allows; more input cannot fix it
+pub const ERR_VARINT_OVERFLOW: &str = "ERR_VARINT_OVERFLOW: Varint too long";
 
 /// Encode a u32 as varint
 pub fn encode_varint32(value: u32) -> Vec<u8> {
@@ -15,6 +20,34 @@ pub fn encode_varint32(value: u32) -> Vec<u8> {
     result
 }
 
+/// Encode a u32 as varint into a stack array, returning the array and the encoded length
+/// Unlike encode_varint32 this does not allocate
+pub fn encode_varint32_array(value: u32) -> ([u8; 5], usize) {
+    let mut result = [0u8; 5];
+    let mut val = value;
+    let mut len = 0;
+    while val >= 0x80 {
+        result[len] = ((val & 0x7F) | 0x80) as u8;
+        val >>= 7;
+        len += 1;
+    }
+    result[len] = val as u8;
+    (result, len + 1)
+}
+
+/// Encode a u32 as a varint padded to exactly 5 bytes with continuation bits
+/// Decoders accept the redundant bytes, so the slot can later be overwritten in place with any u32
+pub fn encode_varint32_padded(value: u32) -> [u8; 5] {
+    let mut result = [0u8; 5];
+    let mut val = value;
+    for byte in result.iter_mut().take(4) {
+        *byte = ((val & 0x7F) | 0x80) as u8;
+        val >>= 7;
+    }
+    result[4] = val as u8;
+    result
+}
+
 /// Encode a u64 as varint
 pub fn encode_varint64(value: u64) -> Vec<u8> {
     let mut result = Vec::new();
@@ -30,21 +63,30 @@ pub fn encode_varint64(value: u64) -> Vec<u8> {
     result
 }
 
+/// Number of bytes in the minimal varint encoding of a u64
+#[inline]
+pub fn varint_len(value: u64) -> usize {
+    let bits = 64 - (value | 1).leading_zeros() as usize;
+    bits.div_ceil(7)
+}
+
 /// Decode varint from buffer starting at offset
+/// Varints longer than 5 bytes or overflowing u32 are rejected with ERR_VARINT_OVERFLOW
 pub fn decode_varint32(buffer: &[u8], offset: &mut usize) -> Result<u32, String> {
     let mut result: u32 = 0;
     let mut shift = 0;
     
     loop {
         if *offset >= buffer.len() {
-            return Err("Unexpected end of buffer".to_string());
+            return Err(ERR_NEED_MORE_DATA.to_string());
         }
         
         let byte = buffer[*offset];
         *offset += 1;
         
-        if shift >= 32 {
-            return Err("Varint too long".to_string());
+        // The 5th byte may only carry the top 4 bits; anything more is a 6th byte or lost bits
+        if shift == 28 && byte > 0x0F {
+            return Err(ERR_VARINT_OVERFLOW.to_string());
         }
         
         result |= ((byte & 0x7F) as u32) << shift;
@@ -60,20 +102,22 @@ pub fn decode_varint32(buffer: &[u8], offset: &mut usize) -> Result<u32, String>
 }
 
 /// Decode varint64 from buffer starting at offset
+/// Varints longer than 10 bytes or overflowing u64 are rejected with ERR_VARINT_OVERFLOW
 pub fn decode_varint64(buffer: &[u8], offset: &mut usize) -> Result<u64, String> {
     let mut result: u64 = 0;
     let mut shift = 0;
     
     loop {
         if *offset >= buffer.len() {
-            return Err("Unexpected end of buffer".to_string());
+            return Err(ERR_NEED_MORE_DATA.to_string());
         }
         
         let byte = buffer[*offset];
         *offset += 1;
         
-        if shift >= 64 {
-            return Err("Varint too long".to_string());
+        // The 10th byte may only carry the top bit; anything more is an 11th byte or lost bits
+        if shift == 63 && byte > 0x01 {
+            return Err(ERR_VARINT_OVERFLOW.to_string());
         }
         
         result |= ((byte & 0x7F) as u64) << shift;
@@ -1,3 +1,6 @@
+/// Largest valid field number (2^29 - 1)
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
 /// Protocol Buffer wire types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -1,162 +1,715 @@
-use crate::varint::{encode_varint32, encode_varint64, zigzag_encode32, zigzag_encode64};
+use crate::bulk::to_le_bytes;
+use crate::hash::Crc32;
+use crate::scan::scan_fields;
+use crate::schema::{encode_message, present_values_from_js, SchemaHandle};
+use crate::sink::{ByteSink, JsCallbackSink};
+use crate::trace::{
+    format_float, format_list, quote_bytes, quote_string, render_text_format, TraceEntry,
+};
+use crate::varint::{
+    encode_varint32, encode_varint32_array, encode_varint32_padded, encode_varint64, zigzag_encode32,
+    zigzag_encode64,
+};
+use crate::wire_type::{encode_tag, WireType, MAX_FIELD_NUMBER};
 use napi::bindgen_prelude::*;
+use napi::{Env, JsFunction, JsObject};
 use napi_derive::napi;
+use smallvec::SmallVec;
+use std::borrow::Cow;
+
+/// Number of bytes the Writer can hold inline before spilling to the heap
+const INLINE_CAPACITY: usize = 64;
+
+/// Backing storage for encoded bytes
+/// Small messages stay inline; larger ones spill to a heap allocation transparently
+type WriterBuffer = SmallVec<[u8; INLINE_CAPACITY]>;
+
+/// Buffered bytes that trigger a flush to the sink when no fork is open
+const DEFAULT_FLUSH_THRESHOLD: usize = 16 * 1024;
+
+/// Accepted JS number ranges (inclusive start, exclusive end) for integer writes
+const BYTE_RANGE: (f64, f64) = (0.0, 256.0);
+pub(crate) const U32_RANGE: (f64, f64) = (0.0, 4294967296.0);
+pub(crate) const I32_RANGE: (f64, f64) = (-2147483648.0, 2147483648.0);
+pub(crate) const U64_RANGE: (f64, f64) = (0.0, 18446744073709551616.0);
+pub(crate) const I64_RANGE: (f64, f64) = (-9223372036854775808.0, 9223372036854775808.0);
+
+/// Reject JS numbers that are fractional, non-finite or outside `range` instead of letting
+/// them truncate into garbage bytes
+pub(crate) fn checked_integer(kind: &str, value: f64, range: (f64, f64)) -> Result<f64> {
+    if value.fract() != 0.0 || value < range.0 || value >= range.1 {
+        return Err(Error::from_reason(format!(
+            "ERR_RANGE: {} value {} is out of range",
+            kind, value
+        )));
+    }
+    Ok(value)
+}
+
+/// Exact value of a BigInt in 0 to 2^64 - 1, rejecting anything else with ERR_RANGE
+fn checked_u64(kind: &str, value: &BigInt) -> Result<u64> {
+    let (negative, bits, lossless) = value.get_u64();
+    if negative || !lossless {
+        return Err(bigint_range_error(kind));
+    }
+    Ok(bits)
+}
+
+/// Exact value of a BigInt in -2^63 to 2^63 - 1, rejecting anything else with ERR_RANGE
+fn checked_i64(kind: &str, value: &BigInt) -> Result<i64> {
+    let magnitude = value.words.first().copied().unwrap_or(0);
+    if value.words.iter().skip(1).any(|&word| word != 0) {
+        return Err(bigint_range_error(kind));
+    }
+    match value.sign_bit {
+        false if magnitude <= i64::MAX as u64 => Ok(magnitude as i64),
+        true if magnitude <= 1 << 63 => Ok((magnitude as i64).wrapping_neg()),
+        _ => Err(bigint_range_error(kind)),
+    }
+}
+
+fn bigint_range_error(kind: &str) -> Error {
+    Error::from_reason(format!("ERR_RANGE: {} BigInt value is out of range", kind))
+}
+
+/// Validate and encode a field tag
+fn tag_bytes(field_number: u32, wire_type: u32) -> Result<Vec<u8>> {
+    let wt = u8::try_from(wire_type)
+        .ok()
+        .and_then(WireType::from_u8)
+        .ok_or_else(|| Error::from_reason("Invalid wire type"))?;
+    if field_number == 0 || field_number > MAX_FIELD_NUMBER {
+        return Err(Error::from_reason("Invalid field number"));
+    }
+    Ok(encode_varint32(encode_tag(field_number, wt)))
+}
+
+/// Encode `length` as a fixed-width (2, 4 or 8 byte) header
+fn fixed_header(length: u64, width: usize, big_endian: bool) -> Result<[u8; 8]> {
+    if !matches!(width, 2 | 4 | 8) {
+        return Err(Error::from_reason("Length prefix width must be 2, 4 or 8 bytes"));
+    }
+    if width < 8 && length >> (width * 8) != 0 {
+        return Err(Error::from_reason(format!(
+            "ERR_RANGE: length {} does not fit in a {} byte prefix",
+            length, width
+        )));
+    }
+    let mut header = [0u8; 8];
+    if big_endian {
+        header[..width].copy_from_slice(&length.to_be_bytes()[8 - width..]);
+    } else {
+        header[..width].copy_from_slice(&length.to_le_bytes()[..width]);
+    }
+    Ok(header)
+}
+
+/// Length header reserved by an open fork
+#[derive(Clone, Copy)]
+enum Prefix {
+    Varint,
+    Fixed { width: usize, big_endian: bool },
+}
 
 /// Binary writer for Protocol Buffer messages with fluent API
 #[napi]
 pub struct Writer {
-    buffer: Vec<u8>,
-    stack: Vec<(usize, usize)>, // Stack for fork/ldelim: (fork_pos, head_pos)
+    buffer: WriterBuffer,
+    stack: Vec<(usize, usize, Prefix)>, // Stack for fork/ldelim: (fork_pos, head_pos, prefix)
+    trace: Option<Vec<TraceEntry>>, // Recorded operations when created with new_with_trace
+    max_size: Option<usize>, // Upper bound on buffer length enforced on every write
+    crc: Option<Crc32>, // Running CRC-32 over committed bytes when tracking is enabled
+    crc_pos: usize, // Number of leading bytes already folded into crc
+    verify: bool, // Check the output parses as protobuf on finish
+    sink: Option<Box<dyn ByteSink>>, // Destination for flushed bytes when streaming
+    flush_threshold: usize, // Buffered length at which bytes are handed to the sink
+    flushed: usize, // Number of bytes already handed to the sink
 }
 
 #[napi]
 impl Writer {
-    /// Create a new Writer
+    /// Create a new Writer, optionally preallocating `capacity` bytes for large messages
+    /// Without a capacity nothing is preallocated, so small messages stay in the inline buffer
     #[napi(constructor)]
-    pub fn new() -> Self {
+    pub fn new(capacity: Option<f64>) -> Result<Self> {
+        let capacity = match capacity {
+            Some(capacity) => checked_integer("capacity", capacity, U32_RANGE)? as usize,
+            None => 0,
+        };
+        Ok(Writer::with_capacity(capacity))
+    }
+
+    /// Create a Writer that also records every write for text-format rendering
+    #[napi(factory)]
+    pub fn new_with_trace() -> Self {
         Writer {
-            // Pre-allocate 256 bytes to reduce reallocations for typical messages
-            // Most protobuf messages are < 256 bytes, avoiding initial growth
-            buffer: Vec::with_capacity(256),
-            stack: Vec::new(),
+            trace: Some(Vec::new()),
+            ..Writer::with_capacity(0)
         }
     }
 
+    /// Create a Writer whose finish() checks that the output parses back as protobuf
+    /// Unclosed forks and malformed fields make finish() throw instead of returning bad bytes
+    #[napi(factory)]
+    pub fn new_with_verification() -> Self {
+        Writer {
+            verify: true,
+            ..Writer::with_capacity(0)
+        }
+    }
+
+    /// Create a Writer that streams its output to `on_bytes(chunk)` instead of holding it all
+    /// Bytes are flushed once at least `flush_threshold` (default 16 KiB) are buffered outside
+    /// any open fork, and the rest by finish(). The callback must not write to this Writer
+    #[napi(factory)]
+    pub fn new_with_sink(env: Env, on_bytes: JsFunction, flush_threshold: Option<f64>) -> Result<Self> {
+        let flush_threshold = match flush_threshold {
+            Some(threshold) => checked_integer("flushThreshold", threshold, U32_RANGE)? as usize,
+            None => DEFAULT_FLUSH_THRESHOLD,
+        };
+        Ok(Writer {
+            sink: Some(Box::new(JsCallbackSink::new(env, on_bytes)?)),
+            flush_threshold,
+            ..Writer::with_capacity(0)
+        })
+    }
+
+    /// Write a field tag for `field_number` and `wire_type`
+    /// Fails on unknown wire types and field numbers outside 1 to 2^29 - 1
+    #[napi]
+    pub fn tag(&mut self, field_number: f64, wire_type: f64) -> Result<&Self> {
+        let field_number = checked_integer("fieldNumber", field_number, U32_RANGE)? as u32;
+        let wire_type = checked_integer("wireType", wire_type, U32_RANGE)? as u32;
+        self.write_tag(field_number, wire_type)?;
+        Ok(self)
+    }
+
     /// Write uint32 (varint encoded)
     #[napi]
-    pub fn uint32(&mut self, value: u32) -> &Self {
-        self.buffer.extend_from_slice(&encode_varint32(value));
-        self
+    pub fn uint32(&mut self, value: f64) -> Result<&Self> {
+        let value = checked_integer("uint32", value, U32_RANGE)? as u32;
+        self.append(&encode_varint32(value))?;
+        self.record("uint32", || value.to_string());
+        Ok(self)
+    }
+
+    /// Write uint32 as a varint padded to exactly 5 bytes
+    /// The fixed width lets the value be patched later without shifting what follows
+    #[napi]
+    pub fn uint32_padded(&mut self, value: f64) -> Result<&Self> {
+        let value = checked_integer("uint32", value, U32_RANGE)? as u32;
+        self.append(&encode_varint32_padded(value))?;
+        self.record("uint32", || value.to_string());
+        Ok(self)
     }
 
     /// Write int32 (varint encoded)
     #[napi]
-    pub fn int32(&mut self, value: i32) -> &Self {
+    pub fn int32(&mut self, value: f64) -> Result<&Self> {
+        let value = checked_integer("int32", value, I32_RANGE)? as i32;
         // Sign-extend to 64-bit for proper varint encoding of negative numbers
         let extended = value as i64;
-        self.buffer.extend_from_slice(&encode_varint64(extended as u64));
-        self
+        self.append(&encode_varint64(extended as u64))?;
+        self.record("int32", || value.to_string());
+        Ok(self)
     }
 
     /// Write sint32 (zigzag + varint encoded)
     #[napi]
-    pub fn sint32(&mut self, value: i32) -> &Self {
+    pub fn sint32(&mut self, value: f64) -> Result<&Self> {
+        let value = checked_integer("sint32", value, I32_RANGE)? as i32;
         let encoded = zigzag_encode32(value);
-        self.buffer.extend_from_slice(&encode_varint32(encoded));
-        self
+        self.append(&encode_varint32(encoded))?;
+        self.record("sint32", || value.to_string());
+        Ok(self)
     }
 
     /// Write uint64 (varint encoded)
     #[napi]
-    pub fn uint64(&mut self, value: i64) -> &Self {
-        self.buffer.extend_from_slice(&encode_varint64(value as u64));
-        self
+    pub fn uint64(&mut self, value: i64) -> Result<&Self> {
+        self.append(&encode_varint64(value as u64))?;
+        self.record("uint64", || (value as u64).to_string());
+        Ok(self)
     }
 
     /// Write int64 (varint encoded)
     #[napi]
-    pub fn int64(&mut self, value: i64) -> &Self {
-        self.buffer.extend_from_slice(&encode_varint64(value as u64));
-        self
+    pub fn int64(&mut self, value: i64) -> Result<&Self> {
+        self.append(&encode_varint64(value as u64))?;
+        self.record("int64", || value.to_string());
+        Ok(self)
     }
 
     /// Write sint64 (zigzag + varint encoded)
     #[napi]
-    pub fn sint64(&mut self, value: i64) -> &Self {
+    pub fn sint64(&mut self, value: i64) -> Result<&Self> {
         let encoded = zigzag_encode64(value);
-        self.buffer.extend_from_slice(&encode_varint64(encoded));
-        self
+        self.append(&encode_varint64(encoded))?;
+        self.record("sint64", || value.to_string());
+        Ok(self)
+    }
+
+    /// Write the exact unsigned 64-bit value of a BigInt as a varint, with no sign handling
+    /// Negative values and values of 2^64 or more are rejected
+    #[napi]
+    pub fn raw_varint(&mut self, value: BigInt) -> Result<&Self> {
+        let (negative, bits, lossless) = value.get_u64();
+        if negative || !lossless {
+            return Err(Error::from_reason("Raw varint must be in the range 0 to 2^64 - 1"));
+        }
+        self.append(&encode_varint64(bits))?;
+        self.record("raw_varint", || bits.to_string());
+        Ok(self)
     }
 
     /// Write bool (varint encoded as 0 or 1)
     #[napi]
-    pub fn bool(&mut self, value: bool) -> &Self {
-        self.buffer.push(if value { 1 } else { 0 });
-        self
+    pub fn bool(&mut self, value: bool) -> Result<&Self> {
+        self.append(&encode_varint32(value as u32))?;
+        self.record("bool", || value.to_string());
+        Ok(self)
+    }
+
+    /// Write a single raw byte (0-255), e.g. a type tag or marker
+    #[napi]
+    pub fn byte(&mut self, value: f64) -> Result<&Self> {
+        let value = checked_integer("byte", value, BYTE_RANGE)? as u8;
+        self.append(&[value])?;
+        self.record("byte", || value.to_string());
+        Ok(self)
     }
 
     /// Write fixed32 (little-endian 4 bytes)
     #[napi]
-    pub fn fixed32(&mut self, value: u32) -> &Self {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        self
+    pub fn fixed32(&mut self, value: f64) -> Result<&Self> {
+        let value = checked_integer("fixed32", value, U32_RANGE)? as u32;
+        self.append(&value.to_le_bytes())?;
+        self.record("fixed32", || value.to_string());
+        Ok(self)
     }
 
     /// Write sfixed32 (little-endian 4 bytes)
     #[napi]
-    pub fn sfixed32(&mut self, value: i32) -> &Self {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        self
+    pub fn sfixed32(&mut self, value: f64) -> Result<&Self> {
+        let value = checked_integer("sfixed32", value, I32_RANGE)? as i32;
+        self.append(&value.to_le_bytes())?;
+        self.record("sfixed32", || value.to_string());
+        Ok(self)
     }
 
     /// Write fixed64 (little-endian 8 bytes)
     #[napi]
-    pub fn fixed64(&mut self, value: i64) -> &Self {
-        self.buffer.extend_from_slice(&(value as u64).to_le_bytes());
-        self
+    pub fn fixed64(&mut self, value: f64) -> Result<&Self> {
+        let value = checked_integer("fixed64", value, U64_RANGE)? as u64;
+        self.append(&value.to_le_bytes())?;
+        self.record("fixed64", || value.to_string());
+        Ok(self)
     }
 
     /// Write sfixed64 (little-endian 8 bytes)
     #[napi]
-    pub fn sfixed64(&mut self, value: i64) -> &Self {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        self
+    pub fn sfixed64(&mut self, value: f64) -> Result<&Self> {
+        let value = checked_integer("sfixed64", value, I64_RANGE)? as i64;
+        self.append(&value.to_le_bytes())?;
+        self.record("sfixed64", || value.to_string());
+        Ok(self)
+    }
+
+    /// Write uint64 from a BigInt without precision loss (0 to 2^64 - 1)
+    #[napi]
+    pub fn uint64_bigint(&mut self, value: BigInt) -> Result<&Self> {
+        let value = checked_u64("uint64", &value)?;
+        self.append(&encode_varint64(value))?;
+        self.record("uint64", || value.to_string());
+        Ok(self)
+    }
+
+    /// Write int64 from a BigInt without precision loss (-2^63 to 2^63 - 1)
+    #[napi]
+    pub fn int64_bigint(&mut self, value: BigInt) -> Result<&Self> {
+        let value = checked_i64("int64", &value)?;
+        self.append(&encode_varint64(value as u64))?;
+        self.record("int64", || value.to_string());
+        Ok(self)
+    }
+
+    /// Write sint64 (zigzag + varint encoded) from a BigInt without precision loss
+    #[napi]
+    pub fn sint64_bigint(&mut self, value: BigInt) -> Result<&Self> {
+        let value = checked_i64("sint64", &value)?;
+        self.append(&encode_varint64(zigzag_encode64(value)))?;
+        self.record("sint64", || value.to_string());
+        Ok(self)
+    }
+
+    /// Write fixed64 from a BigInt without precision loss (0 to 2^64 - 1)
+    #[napi]
+    pub fn fixed64_bigint(&mut self, value: BigInt) -> Result<&Self> {
+        let value = checked_u64("fixed64", &value)?;
+        self.append(&value.to_le_bytes())?;
+        self.record("fixed64", || value.to_string());
+        Ok(self)
+    }
+
+    /// Write sfixed64 from a BigInt without precision loss (-2^63 to 2^63 - 1)
+    #[napi]
+    pub fn sfixed64_bigint(&mut self, value: BigInt) -> Result<&Self> {
+        let value = checked_i64("sfixed64", &value)?;
+        self.append(&value.to_le_bytes())?;
+        self.record("sfixed64", || value.to_string());
+        Ok(self)
     }
 
     /// Write float (32-bit, little-endian)
+    /// NaN, the infinities and -0 keep their meaning; finite values beyond the f32 range
+    /// become infinities
     #[napi]
-    pub fn float(&mut self, value: f64) -> &Self {
+    pub fn float(&mut self, value: f64) -> Result<&Self> {
         let f32_value = value as f32;
-        self.buffer.extend_from_slice(&f32_value.to_le_bytes());
-        self
+        self.append(&f32_value.to_le_bytes())?;
+        self.record("float", || format_float(f32_value as f64));
+        Ok(self)
     }
 
     /// Write double (64-bit, little-endian)
     #[napi]
-    pub fn double(&mut self, value: f64) -> &Self {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        self
+    pub fn double(&mut self, value: f64) -> Result<&Self> {
+        self.append(&value.to_le_bytes())?;
+        self.record("double", || format_float(value));
+        Ok(self)
     }
 
-    /// Write bytes (length-delimited)
+    /// Write bytes (length-delimited) from a Buffer or any Uint8Array
+    /// The bytes are copied once, straight into the output
     #[napi]
-    pub fn bytes(&mut self, value: Buffer) -> &Self {
-        let bytes = value.as_ref();
-        self.buffer.extend_from_slice(&encode_varint32(bytes.len() as u32));
-        self.buffer.extend_from_slice(bytes);
-        self
+    pub fn bytes(&mut self, value: Uint8Array) -> Result<&Self> {
+        let bytes = &value[..];
+        self.append_delimited(bytes)?;
+        self.record("bytes", || quote_bytes(bytes));
+        Ok(self)
     }
 
     /// Write string (UTF-8, length-delimited)
     #[napi]
-    pub fn string(&mut self, value: String) -> &Self {
-        let bytes = value.as_bytes();
-        self.buffer.extend_from_slice(&encode_varint32(bytes.len() as u32));
-        self.buffer.extend_from_slice(bytes);
-        self
+    pub fn string(&mut self, value: String) -> Result<&Self> {
+        self.append_delimited(value.as_bytes())?;
+        self.record("string", || quote_string(&value));
+        Ok(self)
+    }
+
+    /// Write a string field from bytes that are already UTF-8 encoded, skipping the round trip
+    /// through a JS string. Invalid UTF-8 is rejected
+    #[napi]
+    pub fn string_bytes(&mut self, value: Uint8Array) -> Result<&Self> {
+        let text = std::str::from_utf8(&value)
+            .map_err(|_| Error::from_reason("Invalid UTF-8 string"))?;
+        self.append_delimited(text.as_bytes())?;
+        self.record("string", || quote_string(text));
+        Ok(self)
+    }
+
+    /// Write a packed repeated sfixed32 payload (length-delimited, 4 bytes per value)
+    #[napi]
+    pub fn packed_sfixed32(&mut self, values: Int32Array) -> Result<&Self> {
+        self.append_delimited(&to_le_bytes(&values[..]))?;
+        self.record("packed_sfixed32", || format_list(&values[..]));
+        Ok(self)
+    }
+
+    /// Write a packed repeated uint32 payload (length-delimited varints) from a plain number array
+    /// Every element is validated first; the first bad one throws ERR_RANGE naming its index
+    #[napi]
+    pub fn packed_uint32_from_array(&mut self, values: Vec<f64>) -> Result<&Self> {
+        self.packed_integers("uint32", "packed_uint32", &values, U32_RANGE, |v, out| {
+            out.extend_from_slice(&encode_varint32(v as u32))
+        })
+    }
+
+    /// Write a packed repeated int32 payload (length-delimited varints) from a plain number array
+    /// Negative values take 10 bytes each, as for int32()
+    #[napi]
+    pub fn packed_int32_from_array(&mut self, values: Vec<f64>) -> Result<&Self> {
+        self.packed_integers("int32", "packed_int32", &values, I32_RANGE, |v, out| {
+            out.extend_from_slice(&encode_varint64(v as i32 as i64 as u64))
+        })
+    }
+
+    /// Write a packed repeated sint32 payload (length-delimited zigzag varints) from a plain
+    /// number array
+    #[napi]
+    pub fn packed_sint32_from_array(&mut self, values: Vec<f64>) -> Result<&Self> {
+        self.packed_integers("sint32", "packed_sint32", &values, I32_RANGE, |v, out| {
+            out.extend_from_slice(&encode_varint32(zigzag_encode32(v as i32)))
+        })
+    }
+
+    /// Write a packed repeated double payload (length-delimited, 8 bytes per value) from a plain
+    /// number array
+    #[napi]
+    pub fn packed_double_from_array(&mut self, values: Vec<f64>) -> Result<&Self> {
+        self.append_delimited(&to_le_bytes(&values[..]))?;
+        self.record("packed_double", || format_list(&values));
+        Ok(self)
+    }
+
+    /// Write a packed repeated float payload (length-delimited, 4 bytes per value) from a plain
+    /// number array. Values are rounded to single precision
+    #[napi]
+    pub fn packed_float_from_array(&mut self, values: Vec<f64>) -> Result<&Self> {
+        let floats: Vec<f32> = values.iter().map(|&v| v as f32).collect();
+        self.append_delimited(&to_le_bytes(&floats))?;
+        self.record("packed_float", || format_list(&floats));
+        Ok(self)
+    }
+
+    /// Write a packed repeated uint32 payload (length prefix, then each value as a varint)
+    /// An empty array writes a zero length prefix. Validated as packedUint32FromArray()
+    #[napi]
+    pub fn write_packed_uint32(&mut self, values: Vec<f64>) -> Result<&Self> {
+        self.packed_uint32_from_array(values)
+    }
+
+    /// Write a packed repeated sint32 payload (length prefix, then zigzag varints)
+    #[napi]
+    pub fn write_packed_sint32(&mut self, values: Vec<f64>) -> Result<&Self> {
+        self.packed_sint32_from_array(values)
+    }
+
+    /// Write a packed repeated int64 payload (length prefix, then varints)
+    /// Every element is validated first; the first bad one throws ERR_RANGE naming its index
+    #[napi]
+    pub fn write_packed_int64(&mut self, values: Vec<f64>) -> Result<&Self> {
+        self.packed_integers("int64", "packed_int64", &values, I64_RANGE, |v, out| {
+            out.extend_from_slice(&encode_varint64(v as i64 as u64))
+        })
+    }
+
+    /// Write a packed repeated fixed32 payload (length prefix, then 4 bytes per value)
+    /// Every element is validated first; the first bad one throws ERR_RANGE naming its index
+    #[napi]
+    pub fn write_packed_fixed32(&mut self, values: Vec<f64>) -> Result<&Self> {
+        self.packed_integers("fixed32", "packed_fixed32", &values, U32_RANGE, |v, out| {
+            out.extend_from_slice(&(v as u32).to_le_bytes())
+        })
+    }
+
+    /// Write a packed repeated double payload (length prefix, then 8 bytes per value)
+    #[napi]
+    pub fn write_packed_double(&mut self, values: Vec<f64>) -> Result<&Self> {
+        self.packed_double_from_array(values)
+    }
+
+    /// Write float values as consecutive little-endian 4-byte values (no tag or length prefix)
+    /// The typed array's backing memory is copied in a single memcpy on little-endian hosts;
+    /// big-endian hosts convert each element. Wrap in fork/ldelim to emit a packed field
+    #[napi]
+    pub fn float_array(&mut self, values: Float32Array) -> Result<&Self> {
+        self.append(&to_le_bytes(&values[..]))?;
+        self.record("float_array", || format_list(&values[..]));
+        Ok(self)
+    }
+
+    /// Write double values as consecutive little-endian 8-byte values (no tag or length prefix)
+    /// Uses the same single-memcpy fast path as float_array
+    #[napi]
+    pub fn double_array(&mut self, values: Float64Array) -> Result<&Self> {
+        self.append(&to_le_bytes(&values[..]))?;
+        self.record("double_array", || format_list(&values[..]));
+        Ok(self)
+    }
+
+    /// Write fixed32 values as consecutive little-endian 4-byte values (no tag or length prefix)
+    /// Uses the same single-memcpy fast path as float_array
+    #[napi]
+    pub fn fixed32_array(&mut self, values: Uint32Array) -> Result<&Self> {
+        self.append(&to_le_bytes(&values[..]))?;
+        self.record("fixed32_array", || format_list(&values[..]));
+        Ok(self)
+    }
+
+    /// Write a fresh tag for `new_field_number` followed by pre-encoded value bytes verbatim
+    /// For length-delimited fields `value_bytes` must include the length prefix
+    #[napi]
+    pub fn rewrite_field(
+        &mut self,
+        new_field_number: f64,
+        wire_type: f64,
+        value_bytes: Buffer,
+    ) -> Result<&Self> {
+        let new_field_number = checked_integer("fieldNumber", new_field_number, U32_RANGE)? as u32;
+        let wire_type = checked_integer("wireType", wire_type, U32_RANGE)? as u32;
+        let bytes = value_bytes.as_ref();
+        // Append tag and value together so a value that doesn't fit leaves no dangling tag
+        let mut field = tag_bytes(new_field_number, wire_type)?;
+        field.extend_from_slice(bytes);
+        self.append(&field)?;
+        self.record_tag(new_field_number);
+        self.record("raw", || quote_bytes(bytes));
+        Ok(self)
+    }
+
+    /// Write each pre-encoded message as its own tagged, length-delimited occurrence of
+    /// a repeated message field. Nothing is written if the batch would exceed the maximum size
+    #[napi]
+    pub fn repeated_message(&mut self, field_number: f64, messages: Vec<Buffer>) -> Result<&Self> {
+        let field_number = checked_integer("fieldNumber", field_number, U32_RANGE)? as u32;
+        if field_number == 0 || field_number > MAX_FIELD_NUMBER {
+            return Err(Error::from_reason("Invalid field number"));
+        }
+        let tag = encode_varint32(encode_tag(field_number, WireType::LengthDelimited));
+        let total: usize = messages
+            .iter()
+            .map(|message| tag.len() + encode_varint32(message.len() as u32).len() + message.len())
+            .sum();
+        self.ensure_room(total)?;
+        for message in &messages {
+            self.write_tag(field_number, WireType::LengthDelimited as u32)?;
+            self.append_delimited(message.as_ref())?;
+            self.record("bytes", || quote_bytes(message.as_ref()));
+        }
+        Ok(self)
+    }
+
+    /// Write the fields of a compiled schema whose presence bit is set, taking each value from
+    /// `values` at the field's position in the schema. Bit i of `presenceBits` (byte i / 8,
+    /// least significant bit first) marks field i present; present fields are written even
+    /// when they hold a default value, as with proto2 has-bits. Nothing is written on error
+    #[napi]
+    pub fn encode_present(
+        &mut self,
+        schema: &SchemaHandle,
+        presence_bits: Uint8Array,
+        values: JsObject,
+    ) -> Result<&Self> {
+        let present = present_values_from_js(&schema.schema, presence_bits.as_ref(), &values)?;
+        let mut out = Vec::new();
+        encode_message(&schema.schema, &present, &mut out);
+        self.append(&out)?;
+        self.record("raw", || quote_bytes(&out));
+        Ok(self)
+    }
+
+    /// Write another Writer's contents as a length-delimited message
+    /// The body's length is known up front, so no reserved bytes need to be shifted
+    #[napi]
+    pub fn write_message_delimited(&mut self, body: &Writer) -> Result<&Self> {
+        if !body.stack.is_empty() {
+            return Err(Error::from_reason("Cannot delimit a Writer with open forks"));
+        }
+        if body.flushed > 0 {
+            return Err(Error::from_reason("Cannot delimit a Writer whose bytes went to a sink"));
+        }
+        let (bytes, body_trace) = self.source(body);
+        self.append_delimited(&bytes)?;
+        if let Some(trace) = &mut self.trace {
+            match body_trace {
+                Some(body_trace) => {
+                    trace.push(TraceEntry::Open);
+                    trace.extend(body_trace.iter().cloned());
+                    trace.push(TraceEntry::Close);
+                }
+                None => trace.push(TraceEntry::Value {
+                    kind: "bytes",
+                    text: quote_bytes(&bytes),
+                }),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Write an already-encoded message prefixed with its length as a varint32, for framing a
+    /// stream of messages (readDelimited() reads them back). An empty message is a single 0x00
+    #[napi]
+    pub fn write_delimited(&mut self, message: Buffer) -> Result<&Self> {
+        self.append_delimited(&message)?;
+        self.record("bytes", || quote_bytes(&message));
+        Ok(self)
+    }
+
+    /// Append another Writer's bytes as-is, with no tag or length prefix
+    /// Inside an open fork the bytes become part of that fork's content. The other Writer must
+    /// have no open forks, since their length prefixes are not filled in yet
+    #[napi]
+    pub fn merge(&mut self, other: &Writer) -> Result<&Self> {
+        if !other.stack.is_empty() {
+            return Err(Error::from_reason("Cannot merge a Writer with open forks"));
+        }
+        if other.flushed > 0 {
+            return Err(Error::from_reason("Cannot merge a Writer whose bytes went to a sink"));
+        }
+        let (bytes, other_trace) = self.source(other);
+        self.append(&bytes)?;
+        if let Some(trace) = &mut self.trace {
+            match other_trace {
+                Some(other_trace) => trace.extend(other_trace.iter().cloned()),
+                None => trace.push(TraceEntry::Value {
+                    kind: "raw",
+                    text: quote_bytes(&bytes),
+                }),
+            }
+        }
+        Ok(self)
     }
 
     /// Fork the writer for nested messages
     /// Returns the position for later length calculation
     #[napi]
-    pub fn fork(&mut self) -> u32 {
-        let head = self.buffer.len();
+    pub fn fork(&mut self) -> Result<u32> {
         // Reserve space for length prefix (max 5 bytes for varint32)
-        self.buffer.extend_from_slice(&[0, 0, 0, 0, 0]);
-        self.stack.push((head, self.buffer.len()));
-        self.buffer.len() as u32
+        let pos = self.open_fork(5, Prefix::Varint)?;
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry::Open);
+        }
+        Ok(pos)
+    }
+
+    /// Fork the writer for a frame with a fixed-width (2, 4 or 8 byte) length header
+    /// The header is reserved now and backfilled with the body length by ldelim()
+    #[napi]
+    pub fn fork_fixed_length(&mut self, bytes: f64, big_endian: bool) -> Result<u32> {
+        let width = checked_integer("bytes", bytes, U32_RANGE)? as usize;
+        fixed_header(0, width, big_endian)?;
+        self.open_fork(width, Prefix::Fixed { width, big_endian })
+    }
+
+    /// Write a fixed-width (2, 4 or 8 byte) length header as used by non-varint framing
+    #[napi]
+    pub fn fixed_length_prefix(&mut self, length: f64, bytes: f64, big_endian: bool) -> Result<&Self> {
+        let length = checked_integer("length", length, U64_RANGE)? as u64;
+        let width = checked_integer("bytes", bytes, U32_RANGE)? as usize;
+        let header = fixed_header(length, width, big_endian)?;
+        self.append(&header[..width])?;
+        self.record("raw", || quote_bytes(&header[..width]));
+        Ok(self)
     }
 
     /// Write length delimiter after fork
     /// Calculates the length of the nested message and updates the length prefix
+    /// Frames opened with fork_fixed_length() get their fixed-width header backfilled instead
     #[napi]
     pub fn ldelim(&mut self) -> Result<&Self> {
         if self.stack.is_empty() {
             return Err(Error::from_reason("No fork to delimit"));
         }
         
-        let (fork_pos, head_pos) = self.stack.pop().unwrap();
+        let (fork_pos, head_pos, prefix) = self.stack.pop().unwrap();
         let len = self.buffer.len() - head_pos;
+        if let Prefix::Fixed { width, big_endian } = prefix {
+            let header = match fixed_header(len as u64, width, big_endian) {
+                Ok(header) => header,
+                Err(e) => {
+                    // Leave the frame open so the caller can still inspect or reset it
+                    self.stack.push((fork_pos, head_pos, prefix));
+                    return Err(e);
+                }
+            };
+            self.buffer[fork_pos..head_pos].copy_from_slice(&header[..width]);
+            self.commit_crc();
+            return Ok(self);
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry::Close);
+        }
         
         // Encode the length
         let len_bytes = encode_varint32(len as u32);
@@ -173,16 +726,149 @@ impl Writer {
         for (i, &byte) in len_bytes.iter().enumerate() {
             self.buffer[fork_pos + i] = byte;
         }
-        
+
+        self.commit_crc();
+        Ok(self)
+    }
+
+    /// Close every open fork, innermost first, as repeated ldelim() calls would
+    #[napi]
+    pub fn ldelim_all(&mut self) -> Result<&Self> {
+        while !self.stack.is_empty() {
+            self.ldelim()?;
+        }
+        Ok(self)
+    }
+
+    /// Number of forks opened and not yet closed with ldelim()
+    #[napi]
+    pub fn depth(&self) -> u32 {
+        self.stack.len() as u32
+    }
+
+    /// Discard everything written after `position` (a value of len()), e.g. a field that turns
+    /// out to be omitted. Fails past the end, inside or before the length prefix of an open
+    /// fork, before bytes already flushed to a sink, and on Writers created with new_with_trace
+    #[napi]
+    pub fn truncate(&mut self, position: f64) -> Result<&Self> {
+        let position = checked_integer("position", position, U32_RANGE)? as usize;
+        if position > self.len() as usize {
+            return Err(Error::from_reason(format!(
+                "Truncate position {} is past the end ({})",
+                position,
+                self.len()
+            )));
+        }
+        if position < self.flushed {
+            return Err(Error::from_reason("Cannot truncate bytes already flushed to the sink"));
+        }
+        let end = position - self.flushed;
+        if let Some(&(_, head_pos, _)) = self.stack.last() {
+            if end < head_pos {
+                return Err(Error::from_reason("Cannot truncate into an open fork's length prefix"));
+            }
+        }
+        self.truncate_buffer(end)?;
+        Ok(self)
+    }
+
+    /// Discard the most recent open fork along with everything written since it, as if
+    /// fork() had never been called
+    #[napi]
+    pub fn rollback_to_fork(&mut self) -> Result<&Self> {
+        let (fork_pos, _, _) = match self.stack.last() {
+            Some(&fork) => fork,
+            None => return Err(Error::from_reason("No fork to roll back")),
+        };
+        self.truncate_buffer(fork_pos)?;
+        self.stack.pop();
+        Ok(self)
+    }
+
+    /// Limit the total encoded size; writes that would exceed it throw ERR_MAX_SIZE
+    /// and leave the buffer unchanged. Pass null to remove the limit.
+    /// Bytes reserved for the length prefix of an open fork count towards the limit
+    #[napi]
+    pub fn set_max_size(&mut self, max_bytes: Option<f64>) -> Result<&Self> {
+        self.max_size = match max_bytes {
+            Some(max) => Some(checked_integer("maxBytes", max, U32_RANGE)? as usize),
+            None => None,
+        };
+        Ok(self)
+    }
+
+    /// Enable or disable a running CRC-32 that is updated as bytes are appended
+    /// Enabling starts the checksum over the bytes already written
+    #[napi]
+    pub fn track_crc32(&mut self, enabled: bool) -> &Self {
+        self.crc_pos = 0;
+        self.crc = if enabled { Some(Crc32::new()) } else { None };
+        self.commit_crc();
+        self
+    }
+
+    /// Get the running CRC-32 of everything written so far
+    /// Bytes inside an open fork are included once the fork is closed with ldelim
+    #[napi]
+    pub fn running_crc32(&self) -> Result<u32> {
+        match &self.crc {
+            Some(crc) => Ok(crc.value()),
+            None => Err(Error::from_reason("CRC-32 tracking is not enabled for this Writer")),
+        }
+    }
+
+    /// Get the number of bytes written so far, including any already flushed to a sink
+    #[napi]
+    pub fn len(&self) -> u32 {
+        (self.flushed + self.buffer.len()) as u32
+    }
+
+    /// Whether nothing has been written yet
+    #[napi]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Make room for at least `additional` more bytes, so a large write that follows does
+    /// not grow the buffer step by step
+    #[napi]
+    pub fn reserve(&mut self, additional: f64) -> Result<&Self> {
+        let additional = checked_integer("additional", additional, U32_RANGE)? as usize;
+        self.buffer.reserve(additional);
         Ok(self)
     }
 
     /// Finish writing and return the buffer
-    /// Note: This clones the buffer to allow reuse via reset().
-    /// For best performance, only call finish() once per writer lifecycle.
+    /// Note: This clones the buffer so the Writer keeps its contents and can continue writing.
+    /// Use take() instead when the Writer is done with, to avoid the copy.
+    /// A Writer created with new_with_sink flushes everything left and returns an empty buffer.
+    /// Throws while any fork is open, since its length prefix would be left as reserved zeros
     #[napi]
-    pub fn finish(&mut self) -> Buffer {
-        Buffer::from(self.buffer.clone())
+    pub fn finish(&mut self) -> Result<Buffer> {
+        if self.sink.is_some() {
+            if !self.stack.is_empty() {
+                return Err(Error::from_reason("Cannot flush a Writer with open forks"));
+            }
+            self.flush_sink(true)?;
+            return Ok(Buffer::from(Vec::new()));
+        }
+        self.check_output()?;
+        Ok(Buffer::from(self.buffer.to_vec()))
+    }
+
+    /// Finish writing and move the buffer out without copying it, leaving the Writer reset
+    /// and empty. Checks the output as finish() does, and leaves the Writer unchanged if
+    /// that fails
+    #[napi]
+    pub fn take(&mut self) -> Result<Buffer> {
+        let buffer = if self.sink.is_some() {
+            self.finish()?
+        } else {
+            self.check_output()?;
+            Buffer::from(std::mem::replace(&mut self.buffer, WriterBuffer::new()).into_vec())
+        };
+        self.reset();
+        Ok(buffer)
     }
 
     /// Reset the writer to reuse it
@@ -190,6 +876,222 @@ impl Writer {
     pub fn reset(&mut self) -> &Self {
         self.buffer.clear();
         self.stack.clear();
+        if let Some(trace) = &mut self.trace {
+            trace.clear();
+        }
+        if self.crc.is_some() {
+            self.crc = Some(Crc32::new());
+        }
+        self.crc_pos = 0;
+        self.flushed = 0;
         self
     }
+
+    /// Render the recorded writes as protobuf text format: `field_number: value` lines, with
+    /// forks as nested `field_number { ... }` blocks. Fields are numbered, not named, since a
+    /// Writer has no schema; only tags written with tag() (or methods that write their own
+    /// tags) are known, and other writes appear as `# kind: value` comments.
+    /// Only available on Writers created with new_with_trace
+    #[napi]
+    pub fn to_text_format(&self) -> Result<String> {
+        match &self.trace {
+            Some(trace) => Ok(render_text_format(trace)),
+            None => Err(Error::from_reason("Tracing is not enabled for this Writer")),
+        }
+    }
+}
+
+impl Writer {
+    /// Empty Writer preallocating `capacity` bytes, with every option off
+    fn with_capacity(capacity: usize) -> Self {
+        Writer {
+            buffer: WriterBuffer::with_capacity(capacity),
+            stack: Vec::new(),
+            trace: None,
+            max_size: None,
+            crc: None,
+            crc_pos: 0,
+            verify: false,
+            sink: None,
+            flush_threshold: DEFAULT_FLUSH_THRESHOLD,
+            flushed: 0,
+        }
+    }
+
+    /// Fail if the buffer is not a complete message: a fork is still open or, for Writers
+    /// created with new_with_verification, the bytes do not parse back as protobuf
+    fn check_output(&self) -> Result<()> {
+        if !self.stack.is_empty() {
+            return Err(Error::from_reason(format!(
+                "Invalid output: {} unclosed fork(s)",
+                self.stack.len()
+            )));
+        }
+        if self.verify {
+            scan_fields(&self.buffer)
+                .map_err(|e| Error::from_reason(format!("Invalid output: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Bytes and trace of another Writer, copied out when `other` is this Writer
+    /// `w.merge(w)` gets `&mut self` and `&other` for the same object, and appending a slice
+    /// of the buffer to itself would read storage the append may have moved
+    fn source<'a>(&self, other: &'a Writer) -> (Cow<'a, [u8]>, Option<Cow<'a, [TraceEntry]>>) {
+        if std::ptr::eq(self, other) {
+            let trace = other.trace.as_ref().map(|t| Cow::Owned(t.clone()));
+            (Cow::Owned(other.buffer.to_vec()), trace)
+        } else {
+            let trace = other.trace.as_deref().map(Cow::Borrowed);
+            (Cow::Borrowed(&other.buffer[..]), trace)
+        }
+    }
+
+    /// Fail with ERR_MAX_SIZE if `additional` more bytes would exceed the maximum size
+    #[inline]
+    fn ensure_room(&self, additional: usize) -> Result<()> {
+        match self.max_size {
+            Some(max) if self.len() as usize + additional > max => Err(Error::from_reason(format!(
+                "ERR_MAX_SIZE: writing {} bytes at offset {} would exceed the maximum size of {} bytes",
+                additional,
+                self.len(),
+                max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Append raw bytes, enforcing the maximum size
+    #[inline]
+    fn append(&mut self, bytes: &[u8]) -> Result<()> {
+        self.ensure_room(bytes.len())?;
+        self.flush_sink(false)?;
+        self.buffer.extend_from_slice(bytes);
+        self.commit_crc();
+        Ok(())
+    }
+
+    /// Reserve `width` zero bytes for a length header and push the fork, enforcing the maximum
+    /// size. The header is not folded into the CRC, since ldelim() backfills it later
+    fn open_fork(&mut self, width: usize, prefix: Prefix) -> Result<u32> {
+        self.ensure_room(width)?;
+        let head = self.buffer.len();
+        self.buffer.extend_from_slice(&[0u8; 8][..width]);
+        self.stack.push((head, self.buffer.len(), prefix));
+        Ok(self.len())
+    }
+
+    /// Append a varint32 length prefix followed by the payload, enforcing the maximum size
+    #[inline]
+    fn append_delimited(&mut self, payload: &[u8]) -> Result<()> {
+        let (prefix, prefix_len) = encode_varint32_array(payload.len() as u32);
+        let prefix = &prefix[..prefix_len];
+        self.ensure_room(prefix.len() + payload.len())?;
+        self.flush_sink(false)?;
+        self.buffer.extend_from_slice(prefix);
+        self.buffer.extend_from_slice(payload);
+        self.commit_crc();
+        Ok(())
+    }
+
+    /// Shrink the buffer to `end` bytes, restarting the running CRC-32 if it already covers
+    /// some of the removed bytes
+    fn truncate_buffer(&mut self, end: usize) -> Result<()> {
+        if self.trace.is_some() {
+            return Err(Error::from_reason("Cannot discard writes on a Writer with tracing enabled"));
+        }
+        if let Some(crc) = &mut self.crc {
+            if end < self.crc_pos {
+                if self.flushed > 0 {
+                    return Err(Error::from_reason(
+                        "Cannot discard bytes already included in the running CRC-32",
+                    ));
+                }
+                *crc = Crc32::new();
+                crc.update(&self.buffer[..end]);
+                self.crc_pos = end;
+            }
+        }
+        self.buffer.truncate(end);
+        Ok(())
+    }
+
+    /// Fold bytes that can no longer change into the running CRC
+    /// Bytes inside an open fork are deferred until its length prefix is backfilled
+    #[inline]
+    fn commit_crc(&mut self) {
+        if let Some(crc) = &mut self.crc {
+            let end = match self.stack.first() {
+                Some(&(fork_pos, _, _)) => fork_pos,
+                None => self.buffer.len(),
+            };
+            if end > self.crc_pos {
+                crc.update(&self.buffer[self.crc_pos..end]);
+                self.crc_pos = end;
+            }
+        }
+    }
+
+    /// Validate and encode every element of a packed integer field, then write it delimited
+    fn packed_integers(
+        &mut self,
+        kind: &str,
+        trace_kind: &'static str,
+        values: &[f64],
+        range: (f64, f64),
+        encode: impl Fn(f64, &mut Vec<u8>),
+    ) -> Result<&Self> {
+        let mut payload = Vec::with_capacity(values.len());
+        for (index, &value) in values.iter().enumerate() {
+            if checked_integer(kind, value, range).is_err() {
+                return Err(Error::from_reason(format!(
+                    "ERR_RANGE: {} value {} at index {} is out of range",
+                    kind, value, index
+                )));
+            }
+            encode(value, &mut payload);
+        }
+        self.append_delimited(&payload)?;
+        self.record(trace_kind, || format_list(values));
+        Ok(self)
+    }
+
+    /// Hand buffered bytes to the sink once at least flush_threshold have accumulated
+    /// (or unconditionally when `force` is set). Runs before a write, and only with no
+    /// fork open, so fork positions into the buffer stay valid
+    fn flush_sink(&mut self, force: bool) -> Result<()> {
+        if let Some(sink) = &mut self.sink {
+            let ready = force || self.buffer.len() >= self.flush_threshold;
+            if ready && self.stack.is_empty() && !self.buffer.is_empty() {
+                sink.write(&self.buffer)?;
+                self.flushed += self.buffer.len();
+                self.buffer.clear();
+                self.crc_pos = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate and append a field tag
+    fn write_tag(&mut self, field_number: u32, wire_type: u32) -> Result<()> {
+        self.append(&tag_bytes(field_number, wire_type)?)?;
+        self.record_tag(field_number);
+        Ok(())
+    }
+
+    /// Record a field tag in the trace, so the next value or fork renders as that field
+    #[inline]
+    fn record_tag(&mut self, field_number: u32) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry::Tag { field_number });
+        }
+    }
+
+    /// Record a write in the trace, formatting the value only when tracing is enabled
+    #[inline]
+    fn record(&mut self, kind: &'static str, text: impl FnOnce() -> String) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry::Value { kind, text: text() });
+        }
+    }
 }
@@ -3,7 +3,41 @@
  * Tests all data types and edge cases
  */
 
-const { Writer, Reader, encodeVarint, decodeVarint } = require('./index.js');
+const {
+  Writer,
+  Reader,
+  encodeVarint,
+  encodeVarintPadded,
+  varintLength32,
+  varintLength64,
+  decodeVarint,
+  tryDecodeVarint,
+  repackField,
+  canonicalizeMessage,
+  minimizeVarints,
+  messageHash,
+  diffMessages,
+  transformMessage,
+  crc32,
+  bytesEqual,
+  bytesStartsWith,
+  tryParseMessage,
+  thriftEncodeVarint,
+  thriftDecodeVarint,
+  thriftEncodeFieldHeader,
+  thriftDecodeFieldHeader,
+  compileSchema,
+  encodeWith,
+  messagesEqual,
+  encodedSize,
+  decodeWith,
+  decodeWithLength,
+  decodeScalarsInto,
+  decodeFlatSmall,
+  decodeBatchColumnar,
+  protoToJson,
+  jsonToProto,
+} = require('./index.js');
 
 let passed = 0;
 let failed = 0;
@@ -65,6 +99,27 @@ assertBufferEqual(varint4, Buffer.from([0x80, 0x01]), 'encodeVarint(128)');
 const varint5 = encodeVarint(300);
 assertBufferEqual(varint5, Buffer.from([0xac, 0x02]), 'encodeVarint(300)');
 
+// Test varint length boundaries
+assertBufferEqual(encodeVarint(16383), Buffer.from([0xff, 0x7f]), 'encodeVarint(16383)');
+assertBufferEqual(encodeVarint(16384), Buffer.from([0x80, 0x80, 0x01]), 'encodeVarint(16384)');
+assertBufferEqual(encodeVarint(0xffffffff), Buffer.from([0xff, 0xff, 0xff, 0xff, 0x0f]), 'encodeVarint(2^32 - 1)');
+
+// Test padded varints
+assertBufferEqual(encodeVarintPadded(0), Buffer.from([0x80, 0x80, 0x80, 0x80, 0x00]), 'encodeVarintPadded(0)');
+assertBufferEqual(encodeVarintPadded(300), Buffer.from([0xac, 0x82, 0x80, 0x80, 0x00]), 'encodeVarintPadded(300)');
+
+// Test varintLength32()/varintLength64() against the encoder
+for (const value of [0, 1, 127, 128, 16383, 16384, 2 ** 21 - 1, 2 ** 21, 2 ** 28 - 1, 2 ** 28, 0xffffffff]) {
+  assertEqual(varintLength32(value), encodeVarint(value).length, `varintLength32(${value}) matches encodeVarint`);
+}
+for (const value of [2 ** 32, 2 ** 35, 2 ** 53 - 1, -1, -(2 ** 31)]) {
+  assertEqual(varintLength64(value), encodeVarint(value).length, `varintLength64(${value}) matches encodeVarint`);
+}
+assertBufferEqual(encodeVarintPadded(0xffffffff), encodeVarint(0xffffffff), 'encodeVarintPadded(2^32 - 1) matches the minimal form');
+const paddedDecoded = decodeVarint(encodeVarintPadded(300), 0);
+assertEqual(Number(paddedDecoded.value), 300, 'decodeVarint() accepts padded varints');
+assertEqual(paddedDecoded.length, 5, 'Padded varints are always 5 bytes');
+
 // Test varint decoding
 const decoded1 = decodeVarint(Buffer.from([0x00]), 0);
 assertEqual(Number(decoded1.value), 0, 'decodeVarint(0).value');
@@ -83,6 +138,25 @@ assertEqual(decoded4.length, 2, 'decodeVarint(128).length');
 const decoded5 = decodeVarint(Buffer.from([0xac, 0x02]), 0);
 assertEqual(Number(decoded5.value), 300, 'decodeVarint(300).value');
 
+assertEqual(tryDecodeVarint(Buffer.from([0xac, 0x02]), 0).length, 2, 'tryDecodeVarint() decodes complete varints');
+assertEqual(tryDecodeVarint(Buffer.from([0xac]), 0), null, 'tryDecodeVarint() returns null when more data is needed');
+let varintError = '';
+try { decodeVarint(Buffer.from([0xac]), 0); } catch (e) { varintError = e.message; }
+assert(varintError.startsWith('ERR_NEED_MORE_DATA'), 'decodeVarint() reports truncation as ERR_NEED_MORE_DATA');
+varintError = '';
+try { tryDecodeVarint(Buffer.alloc(11, 0xff), 0); } catch (e) { varintError = e.message; }
+assert(varintError.startsWith('ERR_VARINT_OVERFLOW'), 'tryDecodeVarint() reports over-long varints as ERR_VARINT_OVERFLOW');
+varintError = '';
+try { decodeVarint(Buffer.alloc(11, 0xff), 0); } catch (e) { varintError = e.message; }
+assertEqual(varintError, 'ERR_VARINT_OVERFLOW: Varint too long', 'decodeVarint() rejects 11-byte varints');
+varintError = '';
+try { decodeVarint(Buffer.from([0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]), 0); } catch (e) { varintError = e.message; }
+assert(varintError.startsWith('ERR_VARINT_OVERFLOW'), 'decodeVarint() rejects a 10th byte that overflows 64 bits');
+varintError = '';
+try { new Reader(Buffer.from([0xff, 0xff, 0xff, 0xff, 0x1f])).uint32(); } catch (e) { varintError = e.message; }
+assert(varintError.startsWith('ERR_VARINT_OVERFLOW'), 'Reader.uint32() rejects a 5th byte that overflows 32 bits');
+assertEqual(new Reader(Buffer.from([0xff, 0xff, 0xff, 0xff, 0x0f])).uint32(), 4294967295, 'Reader.uint32() accepts 5-byte varints up to 2^32 - 1');
+
 console.log('\n=== Testing Writer ===\n');
 
 // Test uint32
@@ -91,6 +165,21 @@ writer.uint32(150);
 let buffer = writer.finish();
 assertBufferEqual(buffer, Buffer.from([0x96, 0x01]), 'Writer.uint32(150)');
 
+// Test tag
+writer = new Writer();
+writer.tag(1, 0).uint32(42).tag(18, 2);
+assertBufferEqual(writer.finish(), Buffer.from([0x08, 0x2a, 0x92, 0x01]), 'Writer.tag() writes single and multi-byte tags');
+for (const [fieldNumber, wireType] of [[0, 0], [2 ** 29, 0], [1, 6]]) {
+  let tagError = false;
+  try { new Writer().tag(fieldNumber, wireType); } catch (e) { tagError = true; }
+  assert(tagError, `Writer.tag(${fieldNumber}, ${wireType}) is rejected`);
+}
+
+// Test uint32Padded
+writer = new Writer();
+writer.uint32Padded(150);
+assertBufferEqual(writer.finish(), Buffer.from([0x96, 0x81, 0x80, 0x80, 0x00]), 'Writer.uint32Padded(150)');
+
 // Test int32 (positive)
 writer = new Writer();
 writer.int32(150);
@@ -131,6 +220,16 @@ writer = new Writer();
 writer.bool(false);
 buffer = writer.finish();
 assertBufferEqual(buffer, Buffer.from([0x00]), 'Writer.bool(false)');
+assertEqual(new Reader(new Writer().bool(true).finish()).bool(), true, 'Writer.bool(true) round-trips through Reader.bool()');
+assertEqual(new Reader(new Writer().bool(false).finish()).bool(), false, 'Writer.bool(false) round-trips through Reader.bool()');
+
+// Test byte
+writer = new Writer();
+writer.byte(0x00).byte(0xff);
+assertBufferEqual(writer.finish(), Buffer.from([0x00, 0xff]), 'Writer.byte() writes raw bytes');
+let byteError = '';
+try { new Writer().byte(256); } catch (e) { byteError = e.message; }
+assert(byteError.startsWith('ERR_RANGE'), 'Writer.byte() rejects values above 255');
 
 // Test fixed32
 writer = new Writer();
@@ -173,12 +272,154 @@ writer = new Writer();
 writer.bytes(Buffer.from([0x01, 0x02, 0x03]));
 buffer = writer.finish();
 assertBufferEqual(buffer, Buffer.from([0x03, 0x01, 0x02, 0x03]), 'Writer.bytes([0x01, 0x02, 0x03])');
+writer = new Writer();
+writer.bytes(new Uint8Array([0x01, 0x02, 0x03]).subarray(1));
+assertBufferEqual(writer.finish(), Buffer.from([0x02, 0x02, 0x03]), 'Writer.bytes() accepts a plain Uint8Array view');
+
+// Test stringBytes
+writer = new Writer();
+writer.stringBytes(new TextEncoder().encode('héllo'));
+assertBufferEqual(writer.finish(), new Writer().string('héllo').finish(), 'Writer.stringBytes() matches Writer.string()');
+let stringBytesError = '';
+try { new Writer().stringBytes(new Uint8Array([0xc3, 0x28])); } catch (e) { stringBytesError = e.message; }
+assertEqual(stringBytesError, 'Invalid UTF-8 string', 'Writer.stringBytes() rejects invalid UTF-8');
 
 // Test chaining
 writer = new Writer();
 writer.uint32(1).uint32(2).uint32(3);
 buffer = writer.finish();
 assertBufferEqual(buffer, Buffer.from([0x01, 0x02, 0x03]), 'Writer method chaining');
+writer = new Writer();
+assert(writer.uint32(1) === writer && writer.string('x') === writer && writer.reset() === writer, 'Writer methods return the same Writer');
+const chained = new Writer().uint32(8).int64(-2).string('hi').bool(true).double(1.5).bytes(Buffer.from([7])).finish();
+writer = new Writer();
+writer.uint32(8);
+writer.int64(-2);
+writer.string('hi');
+writer.bool(true);
+writer.double(1.5);
+writer.bytes(Buffer.from([7]));
+assertBufferEqual(chained, writer.finish(), 'Chained writes produce the same bytes as separate statements');
+
+// Test spilling past the inline buffer
+writer = new Writer();
+for (let i = 0; i < 100; i++) writer.uint32(i);
+buffer = writer.finish();
+assert(buffer.length === 100, 'Writer spills past inline capacity');
+assertEqual(buffer[99], 99, 'Writer keeps bytes written after spilling');
+
+// Test ERR_RANGE validation of integer arguments
+for (const [method, value] of [['fixed32', -1], ['fixed32', 3.7], ['uint32', 2 ** 32], ['int32', 2 ** 31], ['sfixed32', NaN], ['fixed64', -1]]) {
+  let rangeError = '';
+  try { new Writer()[method](value); } catch (e) { rangeError = e.message; }
+  assert(rangeError.startsWith('ERR_RANGE'), `Writer.${method}(${value}) throws ERR_RANGE`);
+}
+writer = new Writer();
+writer.int32(-(2 ** 31)).fixed32(2 ** 32 - 1);
+assertEqual(writer.len(), 14, 'Writer accepts values at the edges of the range');
+for (const [method, args] of [['tag', [1.5, 0]], ['tag', [1, -1]], ['repeatedMessage', [-1, []]], ['forkFixedLength', [4.5, false]], ['fixedLengthPrefix', [1, 2 ** 32, false]], ['truncate', [-1]], ['reserve', [NaN]], ['rewriteField', [2 ** 32 + 1, 0, Buffer.from([0x01])]], ['setMaxSize', [-1]]]) {
+  let argumentError = '';
+  try { new Writer()[method](...args); } catch (e) { argumentError = e.message; }
+  assert(argumentError.startsWith('ERR_RANGE'), `Writer.${method}(${args.join(', ')}) throws ERR_RANGE`);
+}
+let capacityError = '';
+try { new Writer(1.5); } catch (e) { capacityError = e.message; }
+assert(capacityError.startsWith('ERR_RANGE'), 'new Writer(1.5) throws ERR_RANGE');
+for (const [method, args] of [['tag', [1, 258]], ['rewriteField', [1, 258, Buffer.from([0x01])]]]) {
+  let wireTypeError = '';
+  try { new Writer()[method](...args); } catch (e) { wireTypeError = e.message; }
+  assertEqual(wireTypeError, 'Invalid wire type', `Writer.${method}() rejects wire type 258 instead of truncating it`);
+}
+
+// Test rawVarint
+writer = new Writer();
+writer.rawVarint(2n ** 64n - 1n);
+assertBufferEqual(writer.finish(), Buffer.from([0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]), 'Writer.rawVarint() encodes the full 64-bit range');
+let threw = false;
+try { new Writer().rawVarint(-1n); } catch (e) { threw = true; }
+assert(threw, 'Writer.rawVarint() rejects negative values');
+
+// Test 64-bit BigInt round trips
+writer = new Writer();
+writer.uint64Bigint(2n ** 64n - 1n).int64Bigint(-(2n ** 63n)).sint64Bigint(-(2n ** 63n)).fixed64Bigint(2n ** 64n - 1n).sfixed64Bigint(-(2n ** 63n));
+const bigReader = new Reader(writer.finish());
+assertEqual(bigReader.uint64Bigint(), 2n ** 64n - 1n, 'uint64Bigint round trips u64::MAX');
+assertEqual(bigReader.int64Bigint(), -(2n ** 63n), 'int64Bigint round trips i64::MIN');
+assertEqual(bigReader.sint64Bigint(), -(2n ** 63n), 'sint64Bigint round trips i64::MIN');
+assertEqual(bigReader.fixed64Bigint(), 2n ** 64n - 1n, 'fixed64Bigint round trips u64::MAX');
+assertEqual(bigReader.sfixed64Bigint(), -(2n ** 63n), 'sfixed64Bigint round trips i64::MIN');
+for (const [method, value] of [['uint64Bigint', -1n], ['uint64Bigint', 2n ** 64n], ['int64Bigint', 2n ** 63n], ['sfixed64Bigint', -(2n ** 63n) - 1n]]) {
+  let bigintError = '';
+  try { new Writer()[method](value); } catch (e) { bigintError = e.message; }
+  assert(bigintError.startsWith('ERR_RANGE'), `Writer.${method}(${value}) is rejected`);
+}
+
+// Test newWithVerification
+writer = Writer.newWithVerification();
+writer.uint32(8).uint32(1);
+assertBufferEqual(writer.finish(), Buffer.from([0x08, 0x01]), 'Writer.newWithVerification() accepts valid output');
+writer.fork();
+threw = false;
+try { writer.finish(); } catch (e) { threw = true; }
+assert(threw, 'Writer.newWithVerification() rejects unclosed forks');
+writer = Writer.newWithVerification();
+writer.uint32(0x0f);
+threw = false;
+try { writer.finish(); } catch (e) { threw = true; }
+assert(threw, 'Writer.newWithVerification() rejects malformed fields');
+threw = false;
+try { Writer.newWithVerification().uint32(0).uint32(0).finish(); } catch (e) { threw = e.message.includes('Invalid field number 0'); }
+assert(threw, 'Writer.newWithVerification() rejects field number 0');
+
+// Test newWithSink
+const sinkChunks = [];
+writer = Writer.newWithSink((chunk) => sinkChunks.push(chunk), 4);
+writer.uint32(8).uint32(150).uint32(18);
+writer.fork();
+writer.uint32(8).uint32(1).ldelim();
+assertEqual(sinkChunks.length, 0, 'Writer.newWithSink() holds bytes while a fork is open');
+writer.uint32(24).uint32(1);
+assertEqual(sinkChunks.length, 1, 'Writer.newWithSink() flushes once the threshold is reached');
+for (const threshold of [-1, 1.5]) {
+  let thresholdError = '';
+  try { Writer.newWithSink(() => {}, threshold); } catch (e) { thresholdError = e.message; }
+  assert(thresholdError.startsWith('ERR_RANGE'), `Writer.newWithSink() rejects flush threshold ${threshold}`);
+}
+assertEqual(writer.len(), 9, 'Writer.len() counts flushed bytes');
+assertEqual(writer.finish().length, 0, 'Writer.finish() with a sink returns an empty buffer');
+assertBufferEqual(Buffer.concat(sinkChunks), Buffer.from([0x08, 0x96, 0x01, 0x12, 0x02, 0x08, 0x01, 0x18, 0x01]), 'Writer.newWithSink() streams the encoded bytes in order');
+let sinkDelimitError = '';
+try { new Writer().writeMessageDelimited(writer); } catch (e) { sinkDelimitError = e.message; }
+assertEqual(sinkDelimitError, 'Cannot delimit a Writer whose bytes went to a sink', 'Writer.writeMessageDelimited() rejects a Writer that streamed to a sink');
+
+// Test setMaxSize
+writer = new Writer();
+writer.setMaxSize(4);
+writer.uint32(150).uint32(1);
+let maxSizeError = '';
+try { writer.string('hi'); } catch (e) { maxSizeError = e.message; }
+assert(maxSizeError.startsWith('ERR_MAX_SIZE'), 'Writer.setMaxSize() rejects writes past the limit');
+assertBufferEqual(writer.finish(), Buffer.from([0x96, 0x01, 0x01]), 'Writer.setMaxSize() leaves the buffer unchanged on failure');
+writer.setMaxSize(null);
+writer.string('hi');
+assertEqual(writer.len(), 6, 'Writer.setMaxSize(null) removes the limit');
+writer = new Writer().setMaxSize(4).uint32(1);
+maxSizeError = '';
+try { writer.fork(); } catch (e) { maxSizeError = e.message; }
+assert(maxSizeError.startsWith('ERR_MAX_SIZE'), 'Writer.setMaxSize() counts the bytes fork() reserves');
+assert(writer.len() === 1 && writer.depth() === 0, 'Writer.fork() past the limit leaves the Writer unchanged');
+
+// Test trackCrc32/runningCrc32
+writer = new Writer();
+writer.trackCrc32(true);
+writer.uint32(1).fork();
+writer.uint32(5);
+assertEqual(writer.runningCrc32(), crc32(Buffer.from([0x01])), 'Writer.runningCrc32() excludes bytes inside an open fork');
+writer.ldelim().string('abc');
+assertEqual(writer.runningCrc32(), crc32(writer.finish()), 'Writer.runningCrc32() matches crc32() of the output');
+threw = false;
+try { new Writer().runningCrc32(); } catch (e) { threw = true; }
+assert(threw, 'Writer.runningCrc32() without tracking throws');
 
 // Test reset
 writer = new Writer();
@@ -217,6 +458,14 @@ assertEqual(reader.bool(), true, 'Reader.bool() reads true');
 reader = new Reader(Buffer.from([0x00]));
 assertEqual(reader.bool(), false, 'Reader.bool() reads false');
 
+// Test boolStrict
+assertEqual(new Reader(Buffer.from([0x01])).boolStrict(), true, 'Reader.boolStrict() reads true');
+assertEqual(new Reader(Buffer.from([0x00])).boolStrict(), false, 'Reader.boolStrict() reads false');
+assertEqual(new Reader(Buffer.from([0x96, 0x01])).bool(), true, 'Reader.bool() takes any nonzero value as true');
+let boolError = '';
+try { new Reader(Buffer.from([0x96, 0x01])).boolStrict(); } catch (e) { boolError = e.message; }
+assertEqual(boolError, 'Invalid boolean value 150 at offset 0 while reading boolStrict', 'Reader.boolStrict() rejects other values');
+
 // Test fixed32
 reader = new Reader(Buffer.from([0x78, 0x56, 0x34, 0x12]));
 assertEqual(reader.fixed32(), 0x12345678, 'Reader.fixed32() reads 0x12345678');
@@ -241,6 +490,18 @@ reader = new Reader(buffer);
 const doubleVal = reader.double();
 assert(Math.abs(doubleVal - 3.14159265359) < 0.0001, 'Reader.double() reads ~3.14159265359');
 
+// Test special float/double values round-trip (Object.is distinguishes -0 and matches NaN)
+for (const method of ['float', 'double']) {
+  for (const value of [NaN, Infinity, -Infinity, -0, 0, Number.MIN_VALUE, -Number.MAX_VALUE]) {
+    const expected = method === 'float' ? Math.fround(value) : value;
+    const roundTrip = new Reader(new Writer()[method](value).finish())[method]();
+    assert(Object.is(roundTrip, expected), `${method}(${Object.is(value, -0) ? '-0' : value}) round-trips`);
+  }
+  let specialError = '';
+  try { new Reader(Buffer.alloc(method === 'float' ? 3 : 7))[method](); } catch (e) { specialError = e.message; }
+  assertEqual(specialError, `Unexpected end of buffer at offset 0 while reading ${method}`, `Reader.${method}() reports end of buffer instead of reading past it`);
+}
+
 // Test string
 reader = new Reader(Buffer.from([0x05, 0x68, 0x65, 0x6c, 0x6c, 0x6f]));
 assertEqual(reader.string(), 'hello', 'Reader.string() reads "hello"');
@@ -249,6 +510,435 @@ assertEqual(reader.string(), 'hello', 'Reader.string() reads "hello"');
 reader = new Reader(Buffer.from([0x03, 0x01, 0x02, 0x03]));
 assertBufferEqual(reader.bytes(), Buffer.from([0x01, 0x02, 0x03]), 'Reader.bytes() reads buffer');
 
+// Test bytesRange()
+const rangeSource = Buffer.from([0x03, 0x01, 0x02, 0x03, 0x08]);
+reader = new Reader(rangeSource);
+const byteRange = reader.bytesRange();
+assertBufferEqual(rangeSource.subarray(byteRange.start, byteRange.end), Buffer.from([0x01, 0x02, 0x03]), 'Reader.bytesRange() locates the payload');
+const copyingReader = new Reader(rangeSource);
+copyingReader.bytes();
+assertEqual(reader.pos(), copyingReader.pos(), 'Reader.bytesRange() advances like Reader.bytes()');
+
+// Test subReader(): field 1 = { field 1 = 150 }, then field 2 = 1
+reader = new Reader(Buffer.from([0x0a, 0x03, 0x08, 0x96, 0x01, 0x10, 0x01]));
+reader.tag();
+const nestedReader = reader.subReader();
+assertEqual(reader.pos(), 5, 'Reader.subReader() moves the parent past the payload');
+assertEqual(nestedReader.len(), 3, 'Reader.subReader() is bounded to the payload');
+nestedReader.tag();
+assertEqual(nestedReader.uint32(), 150, 'Reader.subReader() reads the nested message');
+assertEqual(nestedReader.remaining(), 0, 'Reader.subReader() ends at the nested length');
+let subReaderError = '';
+try { nestedReader.uint32(); } catch (e) { subReaderError = e.message; }
+assert(subReaderError.includes('at offset 3'), 'Reader.subReader() reports end of buffer at the nested boundary');
+let subLimitThrew = false;
+try { nestedReader.limit(4); } catch (e) { subLimitThrew = true; }
+assert(subLimitThrew, 'Reader.subReader() cannot be limited past its boundary');
+assertEqual(reader.tag().fieldNumber, 2, 'Reader.subReader() leaves the parent at the next field');
+
+// Test readDelimited(): messages [08 01], [] and [10 02]
+reader = new Reader(Buffer.from([0x02, 0x08, 0x01, 0x00, 0x02, 0x10, 0x02]));
+const delimitedMessages = [];
+let delimited;
+while ((delimited = reader.readDelimited()) !== null) delimitedMessages.push(delimited);
+assertEqual(delimitedMessages.length, 3, 'Reader.readDelimited() reads every message then returns null');
+assertBufferEqual(delimitedMessages[2], Buffer.from([0x10, 0x02]), 'Reader.readDelimited() returns the message bytes');
+assertEqual(delimitedMessages[1].length, 0, 'Reader.readDelimited() handles empty messages');
+let delimitedError = '';
+try { new Reader(Buffer.from([0x05, 0x08])).readDelimited(); } catch (e) { delimitedError = e.message; }
+assert(delimitedError.startsWith('Unexpected end of buffer'), 'Reader.readDelimited() rejects lengths past the end');
+
+// Test setMaxDepth()/setMaxFieldLength()
+const deepMessage = Buffer.from([0x0a, 0x04, 0x0a, 0x02, 0x0a, 0x00]);
+reader = new Reader(deepMessage).setMaxDepth(2);
+reader.tag();
+const depthOne = reader.subReader();
+depthOne.tag();
+const depthTwo = depthOne.subReader();
+depthTwo.tag();
+let depthError = '';
+try { depthTwo.subReader(); } catch (e) { depthError = e.message; }
+assert(depthError.startsWith('Message nesting too deep'), 'Reader.setMaxDepth() bounds subReader() nesting');
+depthError = '';
+try { new Reader(Buffer.from([0x0b, 0x0b, 0x0c, 0x0c])).setMaxDepth(1).skipFields(1); } catch (e) { depthError = e.message; }
+assert(depthError.startsWith('Group nesting too deep'), 'Reader.setMaxDepth() bounds group skipping');
+let fieldLengthError = '';
+try { new Reader(Buffer.from([0x04, 0x01, 0x02, 0x03, 0x04])).setMaxFieldLength(3).bytes(); } catch (e) { fieldLengthError = e.message; }
+assertEqual(fieldLengthError, 'Field length 4 exceeds the maximum of 3 at offset 0 while reading bytes', 'Reader.setMaxFieldLength() rejects long fields');
+fieldLengthError = '';
+try { new Reader(Buffer.from([0x80, 0x80, 0x80, 0x40])).bytes(); } catch (e) { fieldLengthError = e.message; }
+assert(fieldLengthError.startsWith('Field length'), 'Reader rejects declared lengths above 64MB by default');
+
+// Test strictVarints()
+assertEqual(new Reader(Buffer.from([0x96, 0x81, 0x00])).uint32(), 150, 'Reader accepts non-minimal varints by default');
+assertEqual(new Reader(Buffer.from([0x96, 0x01])).strictVarints(true).uint32(), 150, 'Reader.strictVarints() accepts minimal varints');
+reader = new Reader(Buffer.from([0x96, 0x81, 0x00])).strictVarints(true);
+let strictError = '';
+try { reader.uint32(); } catch (e) { strictError = e.message; }
+assertEqual(strictError, 'Non-canonical varint at offset 0 while reading uint32', 'Reader.strictVarints() rejects redundant trailing bytes');
+assertEqual(reader.pos(), 0, 'Reader.strictVarints() leaves the position at the rejected varint');
+strictError = '';
+try { new Reader(Buffer.from([0x88, 0x00, 0x01])).strictVarints(true).tag(); } catch (e) { strictError = e.message; }
+assert(strictError.startsWith('Non-canonical varint'), 'Reader.strictVarints() applies to tags');
+assertEqual(new Reader(Buffer.from([0x00])).strictVarints(true).uint32(), 0, 'Reader.strictVarints() accepts a single zero byte');
+
+// Test byte()
+reader = new Reader(Buffer.from([0x96, 0x01]));
+assertEqual(reader.byte(), 0x96, 'Reader.byte() reads one byte without varint decoding');
+assertEqual(reader.pos(), 1, 'Reader.byte() advances by one');
+reader.byte();
+threw = false;
+try { reader.byte(); } catch (e) { threw = true; }
+assert(threw, 'Reader.byte() past end throws');
+
+// Test readBytesExact()
+reader = new Reader(Buffer.from([0x01, 0x02, 0x03, 0x04]));
+assertBufferEqual(reader.readBytesExact(3), Buffer.from([0x01, 0x02, 0x03]), 'Reader.readBytesExact(3) reads raw bytes');
+assertEqual(reader.pos(), 3, 'Reader.readBytesExact(3) advances position');
+threw = false;
+try { reader.readBytesExact(2); } catch (e) { threw = true; }
+assert(threw, 'Reader.readBytesExact() past end throws');
+
+// Test enumName()
+const statusNames = { 0: 'UNKNOWN', 1: 'ACTIVE', '-1': 'NEGATIVE' };
+reader = new Reader(Buffer.from([0x01, 0x07, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0x09]));
+assertEqual(reader.enumName(statusNames), 'ACTIVE', 'Reader.enumName() maps known values');
+assertEqual(reader.enumName(statusNames), '7', 'Reader.enumName() falls back to the numeric string');
+assertEqual(reader.enumName(statusNames), 'NEGATIVE', 'Reader.enumName() maps negative values');
+threw = false;
+try { reader.enumName(statusNames, true); } catch (e) { threw = true; }
+assert(threw, 'Reader.enumName() in strict mode rejects unknown values');
+assertEqual(reader.pos(), 12, 'Reader.enumName() in strict mode does not consume unknown values');
+
+// Test cString()
+reader = new Reader(Buffer.from([0x68, 0x69, 0x00, 0x05]));
+assertEqual(reader.cString(), 'hi', 'Reader.cString() reads up to the terminator');
+assertEqual(reader.pos(), 3, 'Reader.cString() consumes the terminator');
+threw = false;
+try { reader.cString(); } catch (e) { threw = true; }
+assert(threw, 'Reader.cString() without terminator throws');
+
+// Test packedBool()
+reader = new Reader(Buffer.from([0x04, 0x01, 0x00, 0x81, 0x00, 0x07]));
+const bools = reader.packedBool();
+assertEqual(bools.join(','), 'true,false,true', 'Reader.packedBool() decodes each varint as a bool');
+assertEqual(reader.pos(), 5, 'Reader.packedBool() stops at the packed length');
+
+// Test readPacked*()
+reader = new Reader(Buffer.from([0x03, 0x01, 0x96, 0x01, 0x03, 0x01, 0x02, 0x03, 0x02, 0x01, 0x02]));
+assertEqual(reader.readPackedUint32().join(','), '1,150', 'Reader.readPackedUint32() decodes each element');
+assertEqual(reader.readPackedSint32().join(','), '-1,1,-2', 'Reader.readPackedSint32() zigzag decodes');
+assertEqual(reader.readPackedInt64().join(','), '1,2', 'Reader.readPackedInt64() decodes each element');
+reader = new Reader(Buffer.from([0x08, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x08, 0, 0, 0, 0, 0, 0, 0xf8, 0x3f]));
+assertEqual(reader.readPackedFixed32().join(','), '1,2', 'Reader.readPackedFixed32() decodes little-endian elements');
+assertEqual(reader.readPackedDouble().join(','), '1.5', 'Reader.readPackedDouble() decodes doubles');
+assertEqual(new Reader(Buffer.from([0x00])).readPackedUint32().length, 0, 'Reader.readPackedUint32() reads empty packed fields');
+let packedBoundaryError = '';
+try { new Reader(Buffer.from([0x01, 0x96, 0x01])).readPackedUint32(); } catch (e) { packedBoundaryError = e.message; }
+assert(packedBoundaryError.includes('past the end of the field'), 'Reader.readPackedUint32() rejects elements crossing the declared length');
+threw = false;
+try { new Reader(Buffer.from([0x03, 0x00, 0x00, 0x00])).readPackedFixed32(); } catch (e) { threw = true; }
+assert(threw, 'Reader.readPackedFixed32() rejects lengths that are not a multiple of 4');
+
+// Test quickStats()
+// Field 1 = 150, field 2 = { field 1 = { field 1 = 1 } }, field 3 = fixed32
+reader = new Reader(Buffer.from([0x08, 0x96, 0x01, 0x12, 0x04, 0x0a, 0x02, 0x08, 0x01, 0x1d, 0x00, 0x00, 0x00, 0x00]));
+const stats = reader.quickStats();
+assertEqual(stats.fieldCount, 3, 'Reader.quickStats() counts top-level fields');
+assertEqual(stats.maxDepthApprox, 3, 'Reader.quickStats() approximates nesting depth');
+assertEqual(stats.byteLength, 14, 'Reader.quickStats() reports byte length');
+assertEqual(reader.pos(), 0, 'Reader.quickStats() does not advance');
+
+// Test fieldHashes(): field 1 = 1, field 2 = "a", field 1 = 2
+reader = new Reader(Buffer.from([0x08, 0x01, 0x12, 0x01, 0x61, 0x08, 0x02]));
+const fieldHashes = reader.fieldHashes('fnv1a64');
+assert(fieldHashes instanceof Map, 'Reader.fieldHashes() returns a Map');
+assertEqual([...fieldHashes.keys()].join(','), '1,2', 'Reader.fieldHashes() has one entry per field number, keyed by number');
+assertEqual(fieldHashes.get(2).length, 8, 'Reader.fieldHashes() uses the requested algorithm');
+assert(fieldHashes.get(1).equals(new Reader(Buffer.from([0x08, 0x01, 0x08, 0x02])).fieldHashes('fnv1a64').get(1)), 'Reader.fieldHashes() hashes repeated occurrences together');
+assert(!fieldHashes.get(1).equals(new Reader(Buffer.from([0x08, 0x01, 0x08, 0x03])).fieldHashes('fnv1a64').get(1)), 'Reader.fieldHashes() detects changed values');
+assertEqual(reader.pos(), 0, 'Reader.fieldHashes() does not advance');
+
+// Test oneof()
+// Field 4 = "a", field 9 = 1
+reader = new Reader(Buffer.from([0x22, 0x01, 0x61, 0x48, 0x01]));
+const oneofCases = new Uint32Array([4, 5]);
+const matched = reader.oneof(oneofCases);
+assertEqual(matched.caseFieldNumber, 4, 'Reader.oneof() returns the matching case');
+assertEqual(matched.wireType, 2, 'Reader.oneof() returns the wire type');
+assertEqual(reader.string(), 'a', 'Reader.oneof() leaves the value ready to read');
+assertEqual(reader.oneof(oneofCases), null, 'Reader.oneof() returns null for non-members');
+assertEqual(reader.pos(), 3, 'Reader.oneof() rewinds on non-match');
+reader.skip(2);
+assertEqual(reader.oneof(oneofCases), null, 'Reader.oneof() returns null at end of buffer');
+
+// Test tag()
+// Field 18 fixed64, then wire type 7, then field number 0
+reader = new Reader(Buffer.from([0x91, 0x01, 0x0f, 0x00]));
+const decodedTag = reader.tag();
+assertEqual(decodedTag.fieldNumber, 18, 'Reader.tag() decodes multi-byte field numbers');
+assertEqual(decodedTag.wireType, 1, 'Reader.tag() decodes the wire type');
+threw = false;
+try { reader.tag(); } catch (e) { threw = true; }
+assert(threw && reader.pos() === 2, 'Reader.tag() rejects unknown wire types without advancing');
+reader.skip(1);
+threw = false;
+try { reader.tag(); } catch (e) { threw = true; }
+assert(threw && reader.pos() === 3, 'Reader.tag() rejects field number 0');
+reader = new Reader(Buffer.from([0x08, 0x01]));
+reader.tag();
+reader.uint32();
+reader.rewindField();
+assertEqual(reader.pos(), 0, 'Reader.tag() is tracked by rewindField()');
+
+// Test peekTag()
+reader = new Reader(Buffer.from([0x12, 0x00, 0x0f]));
+const peekedTag = reader.peekTag();
+assertEqual(peekedTag.fieldNumber, 2, 'Reader.peekTag() decodes the field number');
+assertEqual(peekedTag.wireType, 2, 'Reader.peekTag() decodes the wire type');
+assertEqual(reader.pos(), 0, 'Reader.peekTag() does not advance');
+reader.skipType(reader.tag().wireType);
+threw = false;
+try { reader.peekTag(); } catch (e) { threw = true; }
+assert(threw && reader.pos() === 2, 'Reader.peekTag() rejects invalid tags');
+reader.skip(1);
+assertEqual(reader.peekTag(), null, 'Reader.peekTag() returns null at end of buffer');
+
+// Test rewindField()
+reader = new Reader(Buffer.from([0x22, 0x01, 0x61]));
+reader.oneof(oneofCases);
+reader.string();
+reader.rewindField();
+assertEqual(reader.pos(), 0, 'Reader.rewindField() returns to the last tag');
+threw = false;
+try { new Reader(Buffer.from([0x08, 0x01])).rewindField(); } catch (e) { threw = true; }
+assert(threw, 'Reader.rewindField() throws before any tag is read');
+
+// Test readAll()
+// Field 1 = 150, field 2 = "hi", field 3 = fixed32 7, field 4 = fixed64 2^63, field 1 = 2^60
+reader = new Reader(Buffer.from([
+  0x08, 0x96, 0x01,
+  0x12, 0x02, 0x68, 0x69,
+  0x1d, 0x07, 0x00, 0x00, 0x00,
+  0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+  0x08, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x10,
+]));
+const allFields = reader.readAll();
+assertEqual(allFields.length, 5, 'Reader.readAll() returns every field');
+assertEqual(allFields[0].value, 150, 'Reader.readAll() decodes varints as numbers');
+assertBufferEqual(allFields[1].value, Buffer.from('hi'), 'Reader.readAll() returns length-delimited values as Buffers');
+assertEqual(allFields[2].value, 7, 'Reader.readAll() decodes fixed32 as a number');
+assertEqual(allFields[3].value, 2n ** 63n, 'Reader.readAll() decodes fixed64 as a BigInt');
+assertEqual(allFields[4].value, 2n ** 60n, 'Reader.readAll() decodes large varints as BigInt');
+assertEqual(allFields[4].fieldNumber, 1, 'Reader.readAll() keeps repeated occurrences');
+assertEqual(reader.pos(), 31, 'Reader.readAll() consumes the message');
+const groupAll = new Reader(Buffer.from([0x0b, 0x08, 0x01, 0x0c, 0x10, 0x02])).readAll();
+assert(groupAll.length === 2 && groupAll[0].wireType === 3, 'Reader.readAll() reads a group as one field');
+assertBufferEqual(groupAll[0].value, Buffer.from([0x08, 0x01, 0x0c]), 'Reader.readAll() returns a group\'s body and EndGroup tag');
+
+// Test readUntilTag() with field 15 (varint) as the record terminator
+reader = new Reader(Buffer.from([0x08, 0x01, 0x12, 0x01, 0x61, 0x78, 0x08, 0x02, 0x78]));
+let record = reader.readUntilTag(0x78);
+assertEqual(record.length, 2, 'Reader.readUntilTag() returns fields before the terminator');
+assertEqual(reader.pos(), 6, 'Reader.readUntilTag() consumes the terminator');
+record = reader.readUntilTag(0x78);
+assertEqual(record[0].value, 2, 'Reader.readUntilTag() reads the next record');
+threw = false;
+try { new Reader(Buffer.from([0x08, 0x01])).readUntilTag(0x78); } catch (e) { threw = true; }
+assert(threw, 'Reader.readUntilTag() throws when the terminator is missing');
+
+// Test repeatedScalar() on field 4 = [1, 300] (unpacked) followed by field 5 = 9
+reader = new Reader(Buffer.from([0x20, 0x01, 0x20, 0xac, 0x02, 0x28, 0x09]));
+assertEqual(reader.repeatedScalar(4, 'uint32').join(','), '1,300', 'Reader.repeatedScalar() collects consecutive values');
+assertEqual(reader.pos(), 5, 'Reader.repeatedScalar() rewinds to the next field tag');
+reader = new Reader(Buffer.from([0x08, 0x01, 0x08, 0x00]));
+assertEqual(reader.repeatedScalar(1, 'bool').join(','), 'true,false', 'Reader.repeatedScalar() reads bools');
+threw = false;
+try { new Reader(Buffer.from([0x0d, 0x01, 0x00, 0x00, 0x00])).repeatedScalar(1, 'uint32'); } catch (e) { threw = true; }
+assert(threw, 'Reader.repeatedScalar() rejects a mismatched wire type');
+
+// Test readMapEntry() on map<string, int32> field 5 = { a: 7, b: (missing value) }
+reader = new Reader(Buffer.from([0x2a, 0x05, 0x0a, 0x01, 0x61, 0x10, 0x07, 0x2a, 0x03, 0x0a, 0x01, 0x62]));
+const mapEntries = [];
+while (reader.remaining() > 0) {
+  reader.tag();
+  mapEntries.push(reader.readMapEntry('string', 'int32'));
+}
+assertEqual(mapEntries.map((entry) => `${entry.key}=${entry.value}`).join(','), 'a=7,b=0', 'Reader.readMapEntry() decodes string keys and defaults missing values');
+// map<int32, Message>: { 3: { field 1 = 1 } } then an entry missing its key
+reader = new Reader(Buffer.from([0x06, 0x08, 0x03, 0x12, 0x02, 0x08, 0x01, 0x02, 0x10, 0x05]));
+const messageEntry = reader.readMapEntry('int32', 'message');
+assertEqual(messageEntry.key, 3, 'Reader.readMapEntry() decodes int32 keys');
+assertBufferEqual(messageEntry.value, Buffer.from([0x08, 0x01]), 'Reader.readMapEntry() returns message values as Buffers');
+assertEqual(reader.readMapEntry('int32', 'int32').key, 0, 'Reader.readMapEntry() defaults a missing key to zero');
+threw = false;
+try { new Reader(Buffer.from([0x00])).readMapEntry('double', 'int32'); } catch (e) { threw = true; }
+assert(threw, 'Reader.readMapEntry() rejects invalid key types');
+
+// Test nextFieldNumberIs() looping over repeated string field 3 followed by field 4
+reader = new Reader(Buffer.from([0x1a, 0x01, 0x61, 0x1a, 0x01, 0x62, 0x20, 0x01]));
+const repeatedStrings = [];
+while (reader.nextFieldNumberIs(3)) { reader.uint32(); repeatedStrings.push(reader.string()); }
+assertEqual(repeatedStrings.join(','), 'a,b', 'Reader.nextFieldNumberIs() drives a repeated-field loop');
+assertEqual(reader.pos(), 6, 'Reader.nextFieldNumberIs() does not consume the tag');
+reader.skip(2);
+assertEqual(reader.nextFieldNumberIs(4), false, 'Reader.nextFieldNumberIs() returns false at end of buffer');
+
+// Test readGroupFields(): group 1 { field 2 = 5, group 3 { field 1 = 1 } } then field 4 = 9
+reader = new Reader(Buffer.from([0x0b, 0x10, 0x05, 0x1b, 0x08, 0x01, 0x1c, 0x0c, 0x20, 0x09]));
+reader.uint32();
+const groupFields = [];
+reader.readGroupFields(1, (fieldNumber, wireType, value) => groupFields.push({ fieldNumber, wireType, value }));
+assertEqual(groupFields.length, 2, 'Reader.readGroupFields() visits each field in the group');
+assertEqual(groupFields[0].value, 5, 'Reader.readGroupFields() decodes field values');
+assertEqual(groupFields[1].wireType, 3, 'Reader.readGroupFields() reports nested groups');
+const nestedFields = [];
+new Reader(groupFields[1].value).readGroupFields(3, (fieldNumber) => nestedFields.push(fieldNumber));
+assertEqual(nestedFields.join(','), '1', 'Reader.readGroupFields() nested group body is readable');
+assertEqual(reader.pos(), 8, 'Reader.readGroupFields() consumes the EndGroup tag');
+reader = new Reader(Buffer.from([0x0b, 0x10, 0x05, 0x1b, 0x08, 0x01, 0x1c, 0x0c, 0x20, 0x09]));
+reader.uint32();
+let meddledFields = 0;
+reader.readGroupFields(1, () => { meddledFields++; reader.limit(reader.pos()).skipToEnd(); });
+assert(meddledFields === 2 && reader.pos() === 8 && reader.len() === 10, 'Reader.readGroupFields() restores the position and limit a callback changes');
+threw = false;
+try { new Reader(Buffer.from([0x10, 0x05, 0x14])).readGroupFields(1, () => {}); } catch (e) { threw = true; }
+assert(threw, 'Reader.readGroupFields() rejects a mismatched EndGroup');
+
+// Test rawVarint()
+reader = new Reader(Buffer.from([0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]));
+assertEqual(reader.rawVarint(), 2n ** 64n - 1n, 'Reader.rawVarint() reads the full 64-bit range');
+threw = false;
+try { new Reader(Buffer.from([0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02])).rawVarint(); } catch (e) { threw = true; }
+assert(threw, 'Reader.rawVarint() detects overflow');
+
+// Test Reader.fromSlice()
+reader = Reader.fromSlice(Buffer.from([0xff, 0x08, 0x96, 0x01, 0xff]), 1, 3);
+assertEqual(reader.uint32(), 8, 'Reader.fromSlice() starts at the offset');
+assertEqual(reader.uint32(), 150, 'Reader.fromSlice() reads within the slice');
+let sliceError = '';
+try { Reader.fromSlice(Buffer.from([0x01]), 1, 1); } catch (e) { sliceError = e.message; }
+assertEqual(sliceError, 'Slice out of bounds: offset 1 + length 1 exceeds buffer length 1', 'Reader.fromSlice() validates bounds');
+const packedFrames = new ArrayBuffer(5);
+new Uint8Array(packedFrames).set([0x08, 0x01, 0x08, 0x96, 0x01]);
+reader = Reader.fromSlice(Buffer.from(packedFrames), 2, 3);
+reader.tag();
+assertEqual(reader.uint32(), 150, 'Reader.fromSlice() reads a window of an ArrayBuffer');
+
+// Test that Readers read the caller's buffer in place instead of copying it
+const sharedBytes = Buffer.from([0x01, 0xff, 0x02]);
+reader = new Reader(sharedBytes);
+sharedBytes[0] = 0x05;
+assertEqual(reader.uint32(), 5, 'Reader sees writes to its buffer (no copy on construction)');
+reader = Reader.fromSlice(sharedBytes, 2, 1);
+sharedBytes[2] = 0x07;
+assertEqual(reader.uint32(), 7, 'Reader.fromSlice() reads its region in place');
+reader = new Reader(sharedBytes).limit(1);
+threw = false;
+try { reader.skip(2); } catch (e) { threw = true; }
+assert(threw, 'Reader.limit() still bounds reads over a shared buffer');
+assertEqual(reader.clearLimit().skip(2).uint32(), 7, 'Reader.clearLimit() restores the full shared buffer');
+
+// Test fixedLengthPrefix()
+reader = new Reader(Buffer.from([0x00, 0x00, 0x01, 0x02, 0x02, 0x01]));
+assertEqual(reader.fixedLengthPrefix(4, true), 0x102, 'Reader.fixedLengthPrefix() reads big-endian headers');
+assertEqual(reader.fixedLengthPrefix(2, false), 0x102, 'Reader.fixedLengthPrefix() reads little-endian headers');
+threw = false;
+try { new Reader(Buffer.alloc(3)).fixedLengthPrefix(3, true); } catch (e) { threw = true; }
+assert(threw, 'Reader.fixedLengthPrefix() rejects unsupported widths');
+
+// Test skipType() with groups: group 1 { field 2 = 5, group 3 { field 1 = 1 } } then field 4 = 9
+reader = new Reader(Buffer.from([0x0b, 0x10, 0x05, 0x1b, 0x08, 0x01, 0x1c, 0x0c, 0x20, 0x09]));
+reader.skipType(reader.tag().wireType);
+assertEqual(reader.pos(), 8, 'Reader.skipType() skips nested groups');
+reader = new Reader(Buffer.from([0x0b, 0x10, 0x05, 0x0c]));
+reader.skipType(reader.uint32() & 7);
+assertEqual(reader.pos(), 4, 'Reader.skipType() skips groups whose tag was read with uint32()');
+threw = false;
+try { reader = new Reader(Buffer.from([0x0b, 0x10, 0x05, 0x14])); reader.skipType(reader.tag().wireType); } catch (e) { threw = true; }
+assert(threw, 'Reader.skipType() rejects mismatched end groups');
+threw = false;
+try { reader = new Reader(Buffer.from([0x0b, 0x10, 0x05])); reader.skipType(reader.tag().wireType); } catch (e) { threw = true; }
+assert(threw, 'Reader.skipType() rejects unterminated groups');
+threw = false;
+try { new Reader(Buffer.from([0x01, 0x61])).skipType(258); } catch (e) { threw = e.message.includes('Invalid wire type'); }
+assert(threw, 'Reader.skipType() rejects wire types above 255 instead of truncating them');
+
+// Test flexibleLength(): varint 300, then fixed32 258, then an invalid flag
+reader = new Reader(Buffer.from([0x00, 0xac, 0x02, 0x01, 0x02, 0x01, 0x00, 0x00, 0x07]));
+assertEqual(reader.flexibleLength(), 300, 'Reader.flexibleLength() reads varint lengths after flag 0');
+assertEqual(reader.flexibleLength(), 258, 'Reader.flexibleLength() reads fixed32 lengths after flag 1');
+let flagError = '';
+try { reader.flexibleLength(); } catch (e) { flagError = e.message; }
+assert(flagError.includes('0x07') && reader.pos() === 8, 'Reader.flexibleLength() rejects unknown flags without consuming them');
+
+// Test skipToEnd()
+reader = new Reader(Buffer.from([0x08, 0x01, 0x10, 0x02]));
+reader.uint32();
+reader.skipToEnd();
+assertEqual(reader.pos(), 4, 'Reader.skipToEnd() moves to the end');
+
+// Test skipFields(): varint, fixed32, group, string, then field 5 = 7
+reader = new Reader(Buffer.from([0x08, 0x96, 0x01, 0x15, 0x00, 0x00, 0x80, 0x3f, 0x1b, 0x08, 0x01, 0x1c, 0x22, 0x01, 0x61, 0x28, 0x07]));
+reader.skipFields(4);
+assertEqual(reader.pos(), 15, 'Reader.skipFields() skips fields of every wire type');
+let skipFieldsError = '';
+try { reader.skipFields(2); } catch (e) { skipFieldsError = e.message; }
+assert(skipFieldsError.includes('1 of 2'), 'Reader.skipFields() reports hitting the end early');
+
+// Test limit()/clearLimit()
+reader = new Reader(Buffer.from([0x08, 0x01, 0x10, 0x02]));
+reader.limit(2);
+assertEqual(reader.uint32(), 8, 'Reader.limit() allows reads inside the limit');
+reader.uint32();
+threw = false;
+try { reader.uint32(); } catch (e) { threw = e.message.startsWith('ERR_NEED_MORE_DATA'); }
+assert(threw, 'Reader.limit() makes reads past the limit fail as end of buffer');
+reader.clearLimit();
+assertEqual(reader.uint32(), 16, 'Reader.clearLimit() restores the rest of the buffer');
+threw = false;
+try { reader.limit(2); } catch (e) { threw = true; }
+assert(threw, 'Reader.limit() rejects a limit before the current position');
+
+// Test decodeErrorContext()
+reader = new Reader(Buffer.from([0x01, 0x02, 0x03]));
+reader.skip(2);
+reader.decodeErrorContext(true);
+let contextMessage = '';
+try { reader.fixed32(); } catch (e) { contextMessage = e.message; }
+assertEqual(contextMessage, 'Unexpected end of buffer at offset 2 while reading fixed32, context [0x0..0x3]: 01 02 03', 'Reader.decodeErrorContext() adds hex context to errors');
+
+// Test error offsets
+let offsetError = '';
+reader = new Reader(Buffer.from([0x08, 0x96, 0x01, 0x01, 0x02]));
+reader.skip(3);
+try { reader.fixed64(); } catch (e) { offsetError = e.message; }
+assertEqual(offsetError, 'Unexpected end of buffer at offset 3 while reading fixed64', 'Reader errors name the offset and the read');
+offsetError = '';
+try { new Reader(Buffer.from([0x01, 0x96])).skip(1).uint32(); } catch (e) { offsetError = e.message; }
+assertEqual(offsetError, 'ERR_NEED_MORE_DATA: Unexpected end of buffer at offset 1 while reading uint32', 'Reader varint errors report where the varint starts');
+reader = new Reader(Buffer.from([0x05, 0x61, 0x62]));
+offsetError = '';
+try { reader.string(); } catch (e) { offsetError = e.message; }
+assert(offsetError.endsWith('at offset 0 while reading string') && reader.pos() === 0, 'Reader.string() failures leave the position at the length prefix');
+offsetError = '';
+try { new Reader(Buffer.from([0x07])).tag(); } catch (e) { offsetError = e.message; }
+assertEqual(offsetError, 'Invalid wire type at offset 0 while reading tag', 'Reader.tag() errors name the read');
+reader = new Reader(Buffer.from([0x02, 0x01, 0x80]));
+offsetError = '';
+try { reader.readPackedUint32(); } catch (e) { offsetError = e.message; }
+assertEqual(offsetError, 'Packed varint runs past the end of the field at offset 2 while reading readPackedUint32', 'Reader packed errors name the bad element and the read');
+assertEqual(reader.pos(), 0, 'Reader packed failures leave the position at the length prefix');
+reader = new Reader(Buffer.from([0x08, 0x01, 0x10]));
+offsetError = '';
+try { reader.readUntilTag(0x1c); } catch (e) { offsetError = e.message; }
+assert(offsetError.endsWith('at offset 2 while reading readUntilTag') && reader.pos() === 0, 'Reader.readUntilTag() failures restore the position');
+reader = new Reader(Buffer.from([0x08, 0x01]));
+offsetError = '';
+try { reader.skipFields(3); } catch (e) { offsetError = e.message; }
+assertEqual(offsetError, 'Unexpected end of buffer after skipping 1 of 3 fields at offset 2 while reading skipFields', 'Reader.skipFields() errors name the read');
+assertEqual(reader.pos(), 0, 'Reader.skipFields() failures restore the position');
+
 // Test pos()
 reader = new Reader(Buffer.from([0x01, 0x02, 0x03]));
 assertEqual(reader.pos(), 0, 'Reader.pos() initially 0');
@@ -260,6 +950,48 @@ reader = new Reader(Buffer.from([0x01, 0x02, 0x03]));
 reader.skip(2);
 assertEqual(reader.pos(), 2, 'Reader.skip(2) advances position');
 
+// Test tell()/seek()
+reader = new Reader(Buffer.from([0x01, 0x02, 0x03, 0x04]));
+reader.seek(1);
+assertEqual(reader.tell(), 1, 'Reader.seek(1) moves from start');
+reader.seek(2, 1);
+assertEqual(reader.tell(), 3, 'Reader.seek(2, 1) moves from current');
+reader.seek(-2, 2);
+assertEqual(reader.tell(), 2, 'Reader.seek(-2, 2) moves from end');
+assertEqual(reader.uint32(), 3, 'Reader reads after seek');
+threw = false;
+try { reader.seek(5); } catch (e) { threw = true; }
+assert(threw, 'Reader.seek() past end throws');
+
+// Test reset()
+reader = new Reader(Buffer.from([0x08, 0x96, 0x01]));
+const firstPass = reader.tag().fieldNumber + reader.uint32();
+assertEqual(reader.reset().pos(), 0, 'Reader.reset() returns to the start');
+assertEqual(reader.tag().fieldNumber + reader.uint32(), firstPass, 'Reader.reset() allows re-reading the buffer');
+
+// Test clone()
+reader = new Reader(Buffer.from([0x08, 0x96, 0x01, 0x10, 0x02, 0x18, 0x03])).limit(5);
+reader.tag();
+const clonedReader = reader.clone();
+assertEqual(clonedReader.pos(), 1, 'Reader.clone() starts at the same position');
+assertEqual(clonedReader.uint32(), 150, 'Reader.clone() reads the same bytes');
+clonedReader.skipFields(1);
+assertEqual(clonedReader.remaining(), 0, 'Reader.clone() keeps the limit');
+assertEqual(reader.pos(), 1, 'Reader.clone() advances independently of the original');
+assertEqual(reader.uint32(), 150, 'Reader.clone() leaves the original readable');
+
+// Test len()/remaining()
+reader = new Reader(Buffer.from([0x01, 0x02, 0x03, 0x04]));
+assertEqual(reader.len(), 4, 'Reader.len() returns the buffer length');
+reader.skip(1);
+assertEqual(reader.remaining(), 3, 'Reader.remaining() counts unread bytes');
+reader.limit(2);
+assertEqual(reader.len(), 2, 'Reader.len() respects limit()');
+assertEqual(reader.remaining(), 1, 'Reader.remaining() respects limit()');
+reader.clearLimit().seek(0, 2);
+assertEqual(reader.remaining(), 0, 'Reader.remaining() is 0 at the end');
+assert(!reader.isEmpty() && new Reader(Buffer.alloc(0)).isEmpty(), 'Reader.isEmpty() reports an empty readable region');
+
 console.log('\n=== Testing Fork/Ldelim ===\n');
 
 // Test fork/ldelim for nested messages
@@ -282,6 +1014,226 @@ assertEqual(reader.uint32(), 10, 'Read first nested field');
 assertEqual(reader.uint32(), 20, 'Read second nested field');
 assertEqual(reader.uint32(), 2, 'Read field after ldelim');
 
+// Test depth()/ldelimAll() and unbalanced forks
+writer = new Writer();
+writer.uint32(0x0a);
+writer.fork();
+writer.uint32(0x0a);
+writer.fork();
+writer.uint32(8).uint32(1);
+assertEqual(writer.depth(), 2, 'Writer.depth() counts open forks');
+let unbalancedError = '';
+try { writer.finish(); } catch (e) { unbalancedError = e.message; }
+assertEqual(unbalancedError, 'Invalid output: 2 unclosed fork(s)', 'Writer.finish() rejects unclosed forks');
+writer.ldelimAll();
+assertEqual(writer.depth(), 0, 'Writer.ldelimAll() closes every fork');
+assertBufferEqual(writer.finish(), Buffer.from([0x0a, 0x04, 0x0a, 0x02, 0x08, 0x01]), 'Writer.ldelimAll() delimits innermost first');
+
+// Test truncate()/rollbackToFork()
+writer = new Writer().trackCrc32(true).uint32(8).uint32(1);
+const beforeOptional = writer.len();
+writer.uint32(16).uint32(0);
+assertBufferEqual(writer.truncate(beforeOptional).finish(), Buffer.from([0x08, 0x01]), 'Writer.truncate() discards later writes');
+assertEqual(writer.runningCrc32(), crc32(Buffer.from([0x08, 0x01])), 'Writer.truncate() keeps the running CRC-32 in step');
+threw = false;
+try { writer.truncate(3); } catch (e) { threw = true; }
+assert(threw, 'Writer.truncate() rejects positions past the end');
+writer.uint32(0x1a);
+const nestedStart = writer.fork();
+writer.uint32(8);
+threw = false;
+try { writer.truncate(nestedStart - 1); } catch (e) { threw = true; }
+assert(threw, 'Writer.truncate() rejects positions inside an open fork\'s length prefix');
+writer.truncate(nestedStart).uint32(8).uint32(2).ldelim();
+assertBufferEqual(writer.finish(), Buffer.from([0x08, 0x01, 0x1a, 0x02, 0x08, 0x02]), 'Writer.truncate() inside a fork keeps the fork open');
+const beforeSubmessage = writer.len();
+writer.uint32(0x22);
+writer.fork();
+writer.uint32(8).uint32(3);
+writer.rollbackToFork();
+assertEqual(writer.depth(), 0, 'Writer.rollbackToFork() closes the fork');
+assertEqual(writer.len(), beforeSubmessage + 1, 'Writer.rollbackToFork() discards the fork and its contents');
+writer.truncate(beforeSubmessage).uint32(0x28).uint32(4);
+assertBufferEqual(writer.finish(), Buffer.from([0x08, 0x01, 0x1a, 0x02, 0x08, 0x02, 0x28, 0x04]), 'Writer.rollbackToFork() and truncate() drop a whole field');
+threw = false;
+try { new Writer().rollbackToFork(); } catch (e) { threw = true; }
+assert(threw, 'Writer.rollbackToFork() requires an open fork');
+
+// Test fixed-width length frames
+writer = new Writer();
+writer.forkFixedLength(4, true);
+writer.uint32(150);
+writer.ldelim();
+writer.fixedLengthPrefix(0x102, 2, false);
+buffer = writer.finish();
+assertEqual(buffer.toString('hex'), '000000029601' + '0201', 'forkFixedLength()/ldelim() backfills a fixed-width header');
+reader = new Reader(buffer);
+assertEqual(reader.fixedLengthPrefix(4, true), 2, 'Fixed-width frame header round-trips through Reader');
+threw = false;
+try { new Writer().fixedLengthPrefix(0x10000, 2, true); } catch (e) { threw = e.message.startsWith('ERR_RANGE'); }
+assert(threw, 'Writer.fixedLengthPrefix() rejects lengths that do not fit the width');
+
+// Test writeMessageDelimited
+const body = new Writer();
+body.uint32(10).uint32(20);
+assertEqual(body.len(), 2, 'Writer.len() reports bytes written');
+assert(new Writer().isEmpty() && !body.isEmpty(), 'Writer.isEmpty() reports whether anything was written');
+const largePayload = Buffer.alloc(1000, 0xab);
+const presized = new Writer(2048).bytes(largePayload).reserve(4096).bytes(largePayload).finish();
+assertBufferEqual(presized, new Writer().bytes(largePayload).bytes(largePayload).finish(), 'Writer capacity and reserve() do not change the output');
+
+// Test take()
+writer = new Writer().bytes(largePayload);
+const finishedCopy = writer.finish();
+assertBufferEqual(writer.take(), finishedCopy, 'Writer.take() returns the same bytes as finish()');
+assertEqual(writer.len(), 0, 'Writer.take() leaves the Writer empty');
+assertBufferEqual(writer.uint32(1).take(), Buffer.from([0x01]), 'Writer is reusable after take()');
+writer = new Writer().uint32(1);
+const lengthWithFork = writer.fork();
+threw = false;
+try { writer.take(); } catch (e) { threw = true; }
+assert(threw && writer.len() === lengthWithFork, 'Writer.take() keeps the bytes when a fork is open');
+writer = new Writer();
+writer.uint32(1);
+writer.writeMessageDelimited(body);
+writer.uint32(2);
+assertBufferEqual(writer.finish(), Buffer.from([0x01, 0x02, 0x0a, 0x14, 0x02]), 'Writer.writeMessageDelimited() prefixes body length');
+writer = new Writer().bytes(largePayload);
+const selfBody = writer.finish();
+assertBufferEqual(writer.writeMessageDelimited(writer).finish(), new Writer().bytes(largePayload).writeDelimited(selfBody).finish(), 'Writer.writeMessageDelimited() of a Writer into itself delimits its earlier bytes');
+
+// Test writeDelimited
+writer = new Writer().writeDelimited(Buffer.from([0x08, 0x01])).writeDelimited(Buffer.alloc(0));
+const framed = writer.finish();
+assertBufferEqual(framed, Buffer.from([0x02, 0x08, 0x01, 0x00]), 'Writer.writeDelimited() prefixes each message with its length');
+reader = new Reader(framed);
+assertBufferEqual(reader.readDelimited(), Buffer.from([0x08, 0x01]), 'Writer.writeDelimited() round-trips through Reader.readDelimited()');
+
+// Test merge
+writer = new Writer().uint32(1).merge(body).uint32(2);
+assertBufferEqual(writer.finish(), Buffer.from([0x01, 0x0a, 0x14, 0x02]), 'Writer.merge() appends the other Writer\'s bytes');
+writer = new Writer().uint32(0x12);
+writer.fork();
+writer.merge(body);
+writer.ldelim();
+assertBufferEqual(writer.finish(), Buffer.from([0x12, 0x02, 0x0a, 0x14]), 'Writer.merge() inside a fork counts toward its length');
+const openBody = new Writer();
+openBody.fork();
+threw = false;
+try { new Writer().merge(openBody); } catch (e) { threw = true; }
+assert(threw, 'Writer.merge() rejects a Writer with open forks');
+writer = new Writer().bytes(largePayload);
+const selfBytes = writer.finish();
+assertBufferEqual(writer.merge(writer).finish(), Buffer.concat([selfBytes, selfBytes]), 'Writer.merge() of a Writer into itself doubles its bytes');
+
+// Test repeatedMessage
+writer = new Writer();
+writer.repeatedMessage(3, [Buffer.from([0x08, 0x01]), Buffer.alloc(0)]);
+assertBufferEqual(writer.finish(), Buffer.from([0x1a, 0x02, 0x08, 0x01, 0x1a, 0x00]), 'Writer.repeatedMessage() tags and delimits each message');
+writer = new Writer().setMaxSize(4);
+threw = false;
+try { writer.repeatedMessage(3, [Buffer.from([0x08, 0x01]), Buffer.from([0x08, 0x02])]); } catch (e) { threw = e.message.startsWith('ERR_MAX_SIZE'); }
+assert(threw && writer.len() === 0, 'Writer.repeatedMessage() writes nothing when the batch does not fit');
+
+// Test packed writes from plain arrays
+writer = new Writer();
+writer.packedUint32FromArray([1, 300]).packedSint32FromArray([-1, 1]).packedInt32FromArray([-1]);
+assertBufferEqual(writer.finish(), Buffer.from([0x03, 0x01, 0xac, 0x02, 0x02, 0x01, 0x02, 0x0a, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]), 'Writer.packed*FromArray() encode varint payloads');
+writer = new Writer();
+writer.packedDoubleFromArray([1.5]).packedFloatFromArray([1.5]);
+reader = new Reader(writer.finish());
+assertEqual(reader.uint32(), 8, 'Writer.packedDoubleFromArray() prefixes the payload length');
+assertEqual(reader.double(), 1.5, 'Writer.packedDoubleFromArray() writes doubles');
+assertEqual(reader.uint32(), 4, 'Writer.packedFloatFromArray() prefixes the payload length');
+assertEqual(reader.float(), 1.5, 'Writer.packedFloatFromArray() writes floats');
+let packedError = '';
+try { new Writer().packedUint32FromArray([1, 2, -3]); } catch (e) { packedError = e.message; }
+assert(packedError.startsWith('ERR_RANGE') && packedError.includes('index 2'), 'Writer.packedUint32FromArray() reports the offending index');
+writer = new Writer();
+writer.writePackedUint32([1, 300]).writePackedSint32([-1, 1]).writePackedInt64([-1]).writePackedUint32([]);
+assertBufferEqual(writer.finish(), Buffer.from([0x03, 0x01, 0xac, 0x02, 0x02, 0x01, 0x02, 0x0a, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0x00]), 'Writer.writePacked*() encode varint payloads and empty arrays');
+writer = new Writer();
+writer.writePackedFixed32([1, 2]).writePackedDouble([1.5]);
+reader = new Reader(writer.finish());
+assertEqual(reader.readPackedFixed32().join(','), '1,2', 'Writer.writePackedFixed32() round trips');
+assertEqual(reader.readPackedDouble().join(','), '1.5', 'Writer.writePackedDouble() round trips');
+for (const [method, values] of [['writePackedUint32', [1, -1]], ['writePackedSint32', [0.5]], ['writePackedInt64', [2 ** 64]], ['writePackedFixed32', [2 ** 32]]]) {
+  let packedRangeError = '';
+  try { new Writer()[method](values); } catch (e) { packedRangeError = e.message; }
+  assert(packedRangeError.startsWith('ERR_RANGE'), `Writer.${method}() rejects out-of-range values`);
+}
+
+// Test packedSfixed32 round trip
+writer = new Writer();
+writer.packedSfixed32(new Int32Array([1, -2, 0x7fffffff]));
+buffer = writer.finish();
+assertBufferEqual(buffer.subarray(0, 5), Buffer.from([0x0c, 0x01, 0x00, 0x00, 0x00]), 'Writer.packedSfixed32() writes little-endian values');
+reader = new Reader(buffer);
+const sfixedValues = reader.packedSfixed32();
+assert(sfixedValues instanceof Int32Array, 'Reader.packedSfixed32() returns an Int32Array');
+assertEqual(Array.from(sfixedValues).join(','), '1,-2,2147483647', 'Reader.packedSfixed32() round-trips values');
+
+// Test floatArray/doubleArray/fixed32Array
+writer = new Writer();
+writer.fork();
+writer.floatArray(new Float32Array([1.5, -2])).ldelim();
+writer.doubleArray(new Float64Array([0.25]));
+writer.fixed32Array(new Uint32Array([0xdeadbeef]));
+buffer = writer.finish();
+assertEqual(buffer.length, 1 + 8 + 8 + 4, 'Writer bulk arrays write raw values');
+assertEqual(buffer.readFloatLE(1), 1.5, 'Writer.floatArray() writes little-endian floats');
+assertEqual(buffer.readFloatLE(5), -2, 'Writer.floatArray() writes every element');
+assertEqual(buffer.readDoubleLE(9), 0.25, 'Writer.doubleArray() writes little-endian doubles');
+assertEqual(buffer.readUInt32LE(17), 0xdeadbeef, 'Writer.fixed32Array() writes little-endian values');
+
+// Test packedFixed64Big/fixed64ArrayBig
+const bigFixed = Buffer.alloc(16);
+bigFixed.writeBigInt64LE(2n ** 53n + 1n, 0);
+bigFixed.writeBigInt64LE(-1n, 8);
+reader = new Reader(Buffer.concat([Buffer.from([0x10]), bigFixed]));
+const fixedBig = reader.packedFixed64Big();
+assert(fixedBig instanceof BigInt64Array, 'Reader.packedFixed64Big() returns a BigInt64Array');
+assertEqual(fixedBig[0], 2n ** 53n + 1n, 'Reader.packedFixed64Big() keeps values above 2^53 exact');
+assertEqual(fixedBig[1], -1n, 'Reader.packedFixed64Big() reads the second element');
+reader = new Reader(bigFixed);
+assertEqual(reader.fixed64ArrayBig(2)[0], 2n ** 53n + 1n, 'Reader.fixed64ArrayBig() reads raw values');
+assertEqual(reader.pos(), 16, 'Reader.fixed64ArrayBig() advances past the values');
+
+// Test packedSint64Cumsum: deltas [2^53, 1, -2] zigzag encoded
+writer = new Writer();
+writer.fork();
+writer.sint64(2 ** 53).sint64(1).sint64(-2).ldelim();
+reader = new Reader(writer.finish());
+const sums = reader.packedSint64Cumsum();
+assert(sums instanceof BigInt64Array, 'Reader.packedSint64Cumsum() returns a BigInt64Array');
+assertEqual(Array.from(sums).join(','), [2n ** 53n, 2n ** 53n + 1n, 2n ** 53n - 1n].join(','), 'Reader.packedSint64Cumsum() accumulates deltas exactly');
+
+// Test rewriteField
+writer = new Writer();
+writer.rewriteField(7, 2, Buffer.from([0x02, 0x68, 0x69]));
+assertBufferEqual(writer.finish(), Buffer.from([0x3a, 0x02, 0x68, 0x69]), 'Writer.rewriteField() writes new tag and raw value');
+threw = false;
+try { new Writer().rewriteField(0, 0, Buffer.from([0x01])); } catch (e) { threw = true; }
+assert(threw, 'Writer.rewriteField() rejects field number 0');
+
+// Test newWithTrace/toTextFormat
+writer = Writer.newWithTrace();
+writer.tag(1, 0).uint32(1).tag(2, 2).string('hi').tag(3, 2);
+writer.fork();
+writer.tag(1, 2).bytes(Buffer.from([0x41, 0x00])).tag(2, 1).double(NaN);
+writer.ldelim();
+writer.uint32(7);
+assertEqual(writer.toTextFormat(), '1: 1\n2: "hi"\n3 {\n  1: "A\\000"\n  2: nan\n}\n# uint32: 7\n', 'Writer.toTextFormat() renders field numbers, nested messages and untagged writes');
+assertBufferEqual(writer.finish(), Buffer.from([0x08, 0x01, 0x12, 0x02, 0x68, 0x69, 0x1a, 0x0d, 0x0a, 0x02, 0x41, 0x00, 0x11, 0, 0, 0, 0, 0, 0, 0xf8, 0x7f, 0x07]), 'Traced Writer produces the same bytes');
+writer = Writer.newWithTrace().tag(4, 2).writePackedUint32([1, 300]);
+assertEqual(writer.toTextFormat(), '4: [1, 300]\n', 'Writer.toTextFormat() renders packed values as a list');
+writer = Writer.newWithTrace();
+writer.tag(1, 2).string('a\x01\x7f"');
+assertEqual(writer.toTextFormat(), '1: "a\\001\\177\\""\n', 'Writer.toTextFormat() octal-escapes control characters in strings');
+threw = false;
+try { new Writer().toTextFormat(); } catch (e) { threw = true; }
+assert(threw, 'Writer.toTextFormat() without tracing throws');
+
 console.log('\n=== Testing Round-trip Encoding ===\n');
 
 // Test complete round-trip with various data types
@@ -342,6 +1294,264 @@ reader = new Reader(buffer);
 assertEqual(reader.uint32(), 0xFFFFFFFF, 'Max uint32');
 assertEqual(Number(reader.uint64()), 0xFFFFFFFF, 'Large uint64');
 
+console.log('\n=== Testing Message Utilities ===\n');
+
+// Field 1 = 7, packed field 4 = [1, 300], field 5 = 9
+const packedMsg = Buffer.from([0x08, 0x07, 0x22, 0x03, 0x01, 0xac, 0x02, 0x28, 0x09]);
+const unpackedMsg = Buffer.from([0x08, 0x07, 0x20, 0x01, 0x20, 0xac, 0x02, 0x28, 0x09]);
+assertBufferEqual(repackField(packedMsg, 4, false), unpackedMsg, 'repackField() unpacks a packed varint field');
+assertBufferEqual(repackField(unpackedMsg, 4, true), packedMsg, 'repackField() packs an unpacked varint field');
+const mixedRepeated = Buffer.from([0x08, 0x01, 0x0a, 0x02, 0x02, 0x03, 0x08, 0x04]);
+assertBufferEqual(repackField(mixedRepeated, 1, true), Buffer.from([0x0a, 0x04, 0x01, 0x02, 0x03, 0x04]), 'repackField() keeps element order when packing mixed packed and unpacked occurrences');
+for (const wireType of [256, 258]) {
+  let repackError = '';
+  try { repackField(packedMsg, 4, false, wireType); } catch (e) { repackError = e.message; }
+  assertEqual(repackError, 'Invalid element wire type', `repackField() rejects element wire type ${wireType}`);
+}
+
+// Packed fixed32 field 2 = [1, 2]
+const packedFixed = Buffer.from([0x12, 0x08, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00]);
+const unpackedFixed = Buffer.from([0x15, 0x01, 0x00, 0x00, 0x00, 0x15, 0x02, 0x00, 0x00, 0x00]);
+assertBufferEqual(repackField(packedFixed, 2, false, 5), unpackedFixed, 'repackField() unpacks a packed fixed32 field');
+assertBufferEqual(repackField(unpackedFixed, 2, true), packedFixed, 'repackField() packs an unpacked fixed32 field');
+
+// Field 2 = 1, field 1 = 150 with a non-minimal varint
+const unordered = Buffer.from([0x10, 0x01, 0x08, 0x96, 0x81, 0x00]);
+const ordered = Buffer.from([0x08, 0x96, 0x01, 0x10, 0x01]);
+assertBufferEqual(canonicalizeMessage(unordered), ordered, 'canonicalizeMessage() sorts fields and minimizes varints');
+assertBufferEqual(minimizeVarints(unordered), Buffer.from([0x10, 0x01, 0x08, 0x96, 0x01]), 'minimizeVarints() minimizes varints without reordering');
+// Field 2 = 1, then field 3 = { field 1 = 1 } with non-minimal length prefix and value
+const paddedNested = Buffer.from([0x10, 0x01, 0x1a, 0x83, 0x00, 0x08, 0x81, 0x00]);
+assertBufferEqual(minimizeVarints(paddedNested), Buffer.from([0x10, 0x01, 0x1a, 0x03, 0x08, 0x81, 0x00]), 'minimizeVarints() leaves payloads untouched by default');
+assertBufferEqual(minimizeVarints(paddedNested, 1), Buffer.from([0x10, 0x01, 0x1a, 0x02, 0x08, 0x01]), 'minimizeVarints() minimizes nested messages down to the given depth');
+// Field 1 = { field 1 = 1 } with the nested varint padded
+const paddedPayload = Buffer.from([0x0a, 0x03, 0x08, 0x81, 0x00]);
+assertBufferEqual(canonicalizeMessage(paddedPayload), paddedPayload, 'canonicalizeMessage() leaves payloads untouched by default');
+assertBufferEqual(canonicalizeMessage(paddedPayload, 1), Buffer.from([0x0a, 0x02, 0x08, 0x01]), 'canonicalizeMessage() canonicalizes nested messages down to the given depth');
+assertBufferEqual(messageHash(unordered), messageHash(ordered), 'messageHash() ignores field order and varint encoding');
+assertEqual(messageHash(ordered).toString('hex'), '6e07d585d7032fe6444e01d988d3c969bece1ddb1ee60f911b419ddb7855140c', 'messageHash() defaults to SHA-256 of the canonical bytes');
+assertEqual(messageHash(ordered, 'fnv1a64').toString('hex'), '7f1ecaa0bcd120e9', 'messageHash() supports fnv1a64');
+assertEqual(messageHash(paddedPayload).toString('hex'), 'c96cc102138a1835b0ee38f3f5472a59b9dd2c0fc0c0389f421d7373073e0bab', 'messageHash() hashes payloads verbatim by default');
+assert(messageHash(paddedPayload, null, 1).equals(messageHash(Buffer.from([0x0a, 0x02, 0x08, 0x01]), null, 1)), 'messageHash() with a depth ignores nested varint encoding');
+assert(!messageHash(ordered).equals(messageHash(Buffer.from([0x08, 0x01]))), 'messageHash() differs for different messages');
+// a: field 1 = 1, field 2 = 2; b: field 1 = 1, field 2 = 3, field 3 = 4
+const diffs = diffMessages(Buffer.from([0x08, 0x01, 0x10, 0x02]), Buffer.from([0x08, 0x01, 0x10, 0x03, 0x18, 0x04]));
+assertEqual(diffs.length, 2, 'diffMessages() skips identical fields');
+assertEqual(diffs[0].fieldNumber, 2, 'diffMessages() reports changed values');
+assert(diffs[0].inA && diffs[0].inB, 'diffMessages() marks changed fields present in both');
+assert(!diffs[1].inA && diffs[1].inB, 'diffMessages() reports fields missing from one side');
+// Field 1 = 1, field 2 = "ab", field 3 = 5 with a non-minimal varint
+const transformInput = Buffer.from([0x08, 0x01, 0x12, 0x02, 0x61, 0x62, 0x18, 0x85, 0x00]);
+const transformed = transformMessage(transformInput, { 1: () => null, 2: (value) => Buffer.from(value.toString().toUpperCase() + '!') });
+assertBufferEqual(transformed, Buffer.from([0x12, 0x03, 0x41, 0x42, 0x21, 0x18, 0x85, 0x00]), 'transformMessage() rewrites and drops fields and copies the rest verbatim');
+let transformError = '';
+try { transformMessage(transformInput, { 1: () => Buffer.from([0x80]) }); } catch (e) { transformError = e.message; }
+assert(transformError.includes('field 1'), 'transformMessage() rejects replacements that are not a single value');
+const groupInput = Buffer.from([0x0b, 0x08, 0x01, 0x0c, 0x10, 0x02]);
+assertBufferEqual(transformMessage(groupInput, { 1: () => Buffer.from([0x08, 0x02, 0x0c]) }), Buffer.from([0x0b, 0x08, 0x02, 0x0c, 0x10, 0x02]), 'transformMessage() accepts a group body ending in its EndGroup tag');
+for (const body of [[0x08, 0x02], [0x08, 0x02, 0x14], [0x0c, 0x08, 0x02]]) {
+  transformError = '';
+  try { transformMessage(groupInput, { 1: () => Buffer.from(body) }); } catch (e) { transformError = e.message; }
+  assert(transformError.includes('field 1'), `transformMessage() rejects group replacement ${Buffer.from(body).toString('hex')} without a final matching EndGroup tag`);
+}
+assertEqual(crc32(Buffer.from('123456789')), 0xcbf43926, 'crc32() matches the IEEE check value');
+for (const constantTime of [false, true]) {
+  assert(bytesEqual(Buffer.from([1, 2, 3]), Buffer.from([1, 2, 3]), constantTime), `bytesEqual() matches equal buffers (constantTime: ${constantTime})`);
+  assert(!bytesEqual(Buffer.from([1, 2, 3]), Buffer.from([1, 2, 4]), constantTime), `bytesEqual() detects differing bytes (constantTime: ${constantTime})`);
+  assert(!bytesEqual(Buffer.from([1, 2]), Buffer.from([1, 2, 3]), constantTime), `bytesEqual() detects differing lengths (constantTime: ${constantTime})`);
+}
+assert(bytesStartsWith(Buffer.from([0x08, 0x01, 0x10]), Buffer.from([0x08, 0x01])), 'bytesStartsWith() matches a prefix');
+assert(!bytesStartsWith(Buffer.from([0x08]), Buffer.from([0x08, 0x01])), 'bytesStartsWith() rejects a longer prefix');
+
+assertEqual(tryParseMessage(ordered, 0), 5, 'tryParseMessage() returns the length of a complete message');
+assertEqual(tryParseMessage(ordered, 3), 2, 'tryParseMessage() starts at the offset');
+assertEqual(tryParseMessage(Buffer.from([0x0a, 0x05, 0x61]), 0), null, 'tryParseMessage() returns null for a truncated field');
+assertEqual(tryParseMessage(Buffer.from([0x08, 0x96]), 0), null, 'tryParseMessage() returns null for a truncated varint');
+threw = false;
+try { tryParseMessage(Buffer.from([0x0f, 0x01]), 0); } catch (e) { threw = true; }
+assert(threw, 'tryParseMessage() throws on an invalid wire type');
+threw = false;
+try { tryParseMessage(Buffer.from([0x02, 0x05]), 0); } catch (e) { threw = true; }
+assert(threw, 'tryParseMessage() throws on field number 0 even when the field is truncated');
+assertEqual(tryParseMessage(Buffer.from([0x0b, 0x08, 0x01, 0x0c]), 0), 4, 'tryParseMessage() walks a group to its EndGroup tag');
+assertEqual(tryParseMessage(Buffer.from([0x0b, 0x08, 0x01]), 0), null, 'tryParseMessage() returns null for an unterminated group');
+
+console.log('\n=== Testing Thrift Compact Interop ===\n');
+
+assertBufferEqual(thriftEncodeVarint(-1), Buffer.from([0x01]), 'thriftEncodeVarint(-1) zigzag encodes');
+assertBufferEqual(thriftEncodeVarint(150), Buffer.from([0xac, 0x02]), 'thriftEncodeVarint(150)');
+const thriftVarint = thriftDecodeVarint(Buffer.from([0xab, 0x02]), 0);
+assertEqual(Number(thriftVarint.value), -150, 'thriftDecodeVarint() reads -150');
+assertEqual(thriftVarint.length, 2, 'thriftDecodeVarint() reports length');
+
+assertBufferEqual(thriftEncodeFieldHeader(3, 1, 5), Buffer.from([0x25]), 'thriftEncodeFieldHeader() uses short form for small deltas');
+assertBufferEqual(thriftEncodeFieldHeader(100, 1, 8), Buffer.from([0x08, 0xc8, 0x01]), 'thriftEncodeFieldHeader() uses long form for large deltas');
+let header = thriftDecodeFieldHeader(Buffer.from([0x25]), 0, 1);
+assertEqual(header.fieldId, 3, 'thriftDecodeFieldHeader() short form field id');
+assertEqual(header.fieldType, 5, 'thriftDecodeFieldHeader() short form type');
+header = thriftDecodeFieldHeader(Buffer.from([0x08, 0xc8, 0x01]), 0, 1);
+assertEqual(header.fieldId, 100, 'thriftDecodeFieldHeader() long form field id');
+assertEqual(header.length, 3, 'thriftDecodeFieldHeader() long form length');
+header = thriftDecodeFieldHeader(Buffer.from([0x00]), 0, 7);
+assertEqual(header.fieldType, 0, 'thriftDecodeFieldHeader() reads STOP');
+threw = false;
+try { thriftDecodeFieldHeader(Buffer.concat([Buffer.from([0x08]), thriftEncodeVarint(2 ** 32 + 5)]), 0, 0); } catch (e) { threw = e.message.startsWith('ERR_RANGE'); }
+assert(threw, 'thriftDecodeFieldHeader() rejects long-form field ids outside the i16 range');
+threw = false;
+try { thriftDecodeFieldHeader(Buffer.from([0xf5]), 0, 32767); } catch (e) { threw = e.message.startsWith('ERR_RANGE'); }
+assert(threw, 'thriftDecodeFieldHeader() rejects short-form deltas past the i16 range');
+
+console.log('\n=== Testing Schemas ===\n');
+
+const personSchema = compileSchema([
+  { name: 'id', fieldNumber: 1, type: 'uint32' },
+  { name: 'name', fieldNumber: 2, type: 'string' },
+  { name: 'tags', fieldNumber: 3, type: 'sint32', repeated: true },
+  { name: 'child', fieldNumber: 4, type: 'message', fields: [{ name: 'x', fieldNumber: 1, type: 'int64' }] },
+]);
+const personBytes = Buffer.from([0x08, 0x96, 0x01, 0x12, 0x02, 0x68, 0x69, 0x1a, 0x02, 0x01, 0x02, 0x22, 0x02, 0x08, 0x05]);
+assertBufferEqual(encodeWith(personSchema, { id: 150, name: 'hi', tags: [-1, 1], child: { x: 5n } }), personBytes, 'encodeWith() encodes scalars, packed repeated and nested fields');
+assertBufferEqual(encodeWith(personSchema, { id: 150, name: null }), Buffer.from([0x08, 0x96, 0x01]), 'encodeWith() omits missing and null fields');
+for (const [value, object] of [[1.5, { id: 1.5 }], [-1, { id: -1 }], [NaN, { tags: [NaN] }], [2 ** 70, { child: { x: 2 ** 70 } }]]) {
+  let encodeError = '';
+  try { encodeWith(personSchema, object); } catch (e) { encodeError = e.message; }
+  assert(encodeError.startsWith('ERR_RANGE'), `encodeWith() rejects ${value} for an integer field`);
+}
+const person = decodeWith(personSchema, personBytes);
+assertEqual(person.id, 150, 'decodeWith() decodes scalars');
+assertEqual(person.name, 'hi', 'decodeWith() decodes strings');
+assertEqual(person.tags.join(','), '-1,1', 'decodeWith() decodes packed repeated fields');
+assertEqual(person.child.x, 5, 'decodeWith() decodes nested messages');
+const unpackedTags = decodeWith(personSchema, Buffer.from([0x18, 0x01, 0x18, 0x02, 0x50, 0x01]));
+assertEqual(unpackedTags.tags.join(','), '-1,1', 'decodeWith() accepts unpacked repeated fields and skips unknown fields');
+assertEqual(decodeWith(personSchema, Buffer.alloc(0)).tags.length, 0, 'decodeWith() returns empty arrays for absent repeated fields');
+assertBufferEqual(encodeWith(personSchema, { id: 1, name: '' }), Buffer.from([0x08, 0x01, 0x12, 0x00]), 'encodeWith() writes empty strings as present');
+assertBufferEqual(encodeWith(personSchema, { id: 1, name: '' }, true), Buffer.from([0x08, 0x01]), 'encodeWith() skipEmpty omits empty strings');
+const sparseSchema = compileSchema([{ name: 'a', fieldNumber: 1, type: 'uint32' }, { name: 'b', fieldNumber: 5, type: 'uint32' }]);
+// Unknown field 3 = 7 and field 9 = 1, preserved from an earlier decode
+const preservedUnknowns = Buffer.from([0x18, 0x07, 0x48, 0x01]);
+assertBufferEqual(encodeWith(sparseSchema, { a: 1, b: 2 }, false, preservedUnknowns), Buffer.from([0x08, 0x01, 0x18, 0x07, 0x28, 0x02, 0x48, 0x01]), 'encodeWith() merges unknown fields in field-number order');
+assertBufferEqual(encodeWith(sparseSchema, {}, false, preservedUnknowns), preservedUnknowns, 'encodeWith() keeps unknown fields when no known fields are set');
+assertEqual(encodedSize(personSchema, { id: 150, name: 'hi', tags: [-1, 1], child: { x: 5n } }), personBytes.length, 'encodedSize() matches the encoded length of nested messages');
+const largePerson = { id: 1, name: 'x'.repeat(200), child: { x: -1 } };
+assertEqual(encodedSize(personSchema, largePerson), encodeWith(personSchema, largePerson).length, 'encodedSize() accounts for multi-byte length prefixes and 10-byte negatives');
+assertEqual(encodedSize(personSchema, { id: 1, name: '' }, true), 2, 'encodedSize() honours skipEmpty');
+assert('name' in decodeWith(personSchema, Buffer.from([0x12, 0x00])), 'decodeWith() marks present empty strings');
+assert(!('name' in decodeWith(personSchema, Buffer.from([0x08, 0x01]))), 'decodeWith() leaves absent fields unset');
+assert(messagesEqual(personBytes, Buffer.from([0x22, 0x02, 0x08, 0x05, 0x18, 0x01, 0x18, 0x02, 0x12, 0x02, 0x68, 0x69, 0x08, 0x96, 0x81, 0x00]), personSchema), 'messagesEqual() ignores order, packing and varint padding');
+assert(messagesEqual(Buffer.from([0x08, 0x00]), Buffer.alloc(0), personSchema), 'messagesEqual() treats explicit defaults as absent');
+assert(!messagesEqual(Buffer.from([0x08, 0x01]), Buffer.from([0x08, 0x02]), personSchema), 'messagesEqual() detects different values');
+const strictSchema = compileSchema([{ name: 'id', fieldNumber: 1, type: 'uint32' }], true);
+assertEqual(decodeWith(strictSchema, Buffer.from([0x08, 0x01])).id, 1, 'Strict schemas decode known fields');
+let unknownFieldError = '';
+try { decodeWith(strictSchema, Buffer.from([0x08, 0x01, 0x50, 0x01])); } catch (e) { unknownFieldError = e.message; }
+assert(unknownFieldError.startsWith('ERR_UNKNOWN_FIELD') && unknownFieldError.includes('10'), 'Strict schemas reject unknown fields with ERR_UNKNOWN_FIELD');
+const personStream = Buffer.concat([personBytes, Buffer.from([0x08, 0x07])]);
+const firstPerson = decodeWithLength(personSchema, personStream, 0, personBytes.length);
+assertEqual(firstPerson.bytesConsumed, personBytes.length, 'decodeWithLength() consumes the given length');
+assertEqual(firstPerson.value.name, 'hi', 'decodeWithLength() decodes the first message');
+const secondPerson = decodeWithLength(personSchema, personStream, firstPerson.bytesConsumed);
+assertEqual(secondPerson.value.id, 7, 'decodeWithLength() decodes from an offset');
+assertEqual(secondPerson.bytesConsumed, 2, 'decodeWithLength() consumes to the end of the buffer');
+const repeatedIds = Buffer.from([0x08, 0x01, 0x08, 0x02]);
+assertEqual(decodeWithLength(personSchema, repeatedIds, 0, 2).value.id, 1, 'decodeWithLength() stops at the given length');
+const mergedIds = decodeWithLength(personSchema, repeatedIds);
+assert(mergedIds.value.id === 2 && mergedIds.bytesConsumed === 4, 'decodeWithLength() without a length merges concatenated messages');
+const outOfOrder = decodeWithLength(personSchema, Buffer.from([0x12, 0x02, 0x68, 0x69, 0x08, 0x01]));
+assert(outOfOrder.value.id === 1 && outOfOrder.value.name === 'hi' && outOfOrder.bytesConsumed === 6, 'decodeWithLength() reads fields in any order');
+threw = false;
+try { decodeWithLength(personSchema, repeatedIds, 2, 3); } catch (e) { threw = true; }
+assert(threw, 'decodeWithLength() rejects lengths past the end of the buffer');
+const personEvents = [];
+new Reader(personBytes).parseEvents(personSchema, {
+  field: (name, fieldNumber, value) => personEvents.push(`${name}=${value}`),
+  startMessage: (name) => personEvents.push(`<${name}>`),
+  endMessage: (name) => personEvents.push(`</${name}>`),
+});
+assertEqual(personEvents.join(' '), 'id=150 name=hi tags=-1 tags=1 <child> x=5 </child>', 'Reader.parseEvents() reports fields, packed elements and nested messages in order');
+const fieldOnlyEvents = [];
+const eventReader = new Reader(personBytes).parseEvents(personSchema, { field: (name) => fieldOnlyEvents.push(name) });
+assertEqual(fieldOnlyEvents.join(','), 'id,name,tags,tags,x', 'Reader.parseEvents() allows omitted handlers');
+const presentWriter = new Writer();
+presentWriter.encodePresent(personSchema, new Uint8Array([0b0101]), [0, 'skipped', [-1]]);
+assertBufferEqual(presentWriter.finish(), Buffer.from([0x08, 0x00, 0x1a, 0x01, 0x01]), 'Writer.encodePresent() writes only present fields, including defaults');
+let presenceError = '';
+try { new Writer().encodePresent(personSchema, new Uint8Array([0b0010]), [1]); } catch (e) { presenceError = e.message; }
+assert(presenceError.includes('name'), 'Writer.encodePresent() rejects present fields without a value');
+assertEqual(eventReader.pos(), personBytes.length, 'Reader.parseEvents() consumes the message');
+const redacted = new Reader(personBytes).decodeExcluding(personSchema, new Uint32Array([2, 4]));
+assertEqual(redacted.id, 150, 'Reader.decodeExcluding() decodes fields not in the denylist');
+assert(!('name' in redacted) && !('child' in redacted), 'Reader.decodeExcluding() drops denylisted fields');
+assertEqual(new Reader(Buffer.from([0x08, 0x01, 0x50, 0x01])).decodeExcluding(strictSchema, new Uint32Array([10])).id, 1, 'Reader.decodeExcluding() skips denylisted fields unknown to strict schemas');
+const pointSchema = compileSchema([
+  { name: 'x', fieldNumber: 1, type: 'double' },
+  { name: 'id', fieldNumber: 2, type: 'uint64' },
+  { name: 'ok', fieldNumber: 3, type: 'bool' },
+  { name: 'label', fieldNumber: 4, type: 'string' },
+]);
+const columns = decodeBatchColumnar([
+  encodeWith(pointSchema, { x: 1.5, id: 7, ok: true, label: 'a' }),
+  encodeWith(pointSchema, { id: 2n ** 60n }),
+], pointSchema);
+assert(columns.x instanceof Float64Array, 'decodeBatchColumnar() builds Float64Array columns for doubles');
+assertEqual(Array.from(columns.x).join(','), '1.5,0', 'decodeBatchColumnar() fills missing fields with defaults');
+assertEqual(columns.id[1], 2n ** 60n, 'decodeBatchColumnar() keeps 64-bit values exact');
+assertEqual(Array.from(columns.ok).join(','), '1,0', 'decodeBatchColumnar() stores bools as bytes');
+assertEqual(columns.label, undefined, 'decodeBatchColumnar() skips non-numeric fields');
+const pointColumns = { 1: new Float64Array(2), 2: new BigUint64Array(2) };
+new Reader(encodeWith(pointSchema, { x: 2.5, id: 9 })).collectInto(pointSchema, pointColumns, 1);
+assertEqual(pointColumns[1][1], 2.5, 'Reader.collectInto() writes doubles at the given row');
+assertEqual(pointColumns[2][1], 9n, 'Reader.collectInto() writes 64-bit values into BigInt columns');
+assertEqual(pointColumns[1][0], 0, 'Reader.collectInto() leaves other rows untouched');
+let rowError = '';
+try { new Reader(encodeWith(pointSchema, { x: 1 })).collectInto(pointSchema, pointColumns, 2); } catch (e) { rowError = e.message; }
+assert(rowError.includes('out of range'), 'Reader.collectInto() rejects rows past the end of a column');
+
+const jsonSchema = compileSchema([
+  { name: 'user_id', fieldNumber: 1, type: 'int64' },
+  { name: 'avatar', fieldNumber: 2, type: 'bytes' },
+  { name: 'color', fieldNumber: 3, type: 'enum', enumValues: { RED: 0, BLUE: 2 } },
+  { name: 'scores', fieldNumber: 4, type: 'float', repeated: true },
+  { name: 'note', fieldNumber: 5, type: 'string' },
+]);
+const jsonBytes = encodeWith(jsonSchema, { user_id: 2n ** 60n, avatar: Buffer.from('fo'), color: 2, scores: [0.1, 2], note: 'a"b' });
+assertEqual(protoToJson(jsonSchema, jsonBytes), '{"userId":"1152921504606846976","avatar":"Zm8=","color":"BLUE","scores":[0.1,2],"note":"a\\"b"}', 'protoToJson() applies the proto3 JSON mapping');
+assertEqual(protoToJson(jsonSchema, Buffer.from([0x18, 0x07])), '{"color":7}', 'protoToJson() writes unknown enum values as numbers');
+assertBufferEqual(jsonToProto(jsonSchema, protoToJson(jsonSchema, jsonBytes)), jsonBytes, 'jsonToProto() round-trips protoToJson() output');
+assertBufferEqual(jsonToProto(jsonSchema, '{"user_id": 5, "color": 2, "note": null}'), Buffer.from([0x08, 0x05, 0x18, 0x02]), 'jsonToProto() accepts original names, numbers and null');
+threw = false;
+try { jsonToProto(jsonSchema, '{"extra": 1}'); } catch (e) { threw = true; }
+assert(threw, 'jsonToProto() rejects unknown fields by default');
+assertEqual(jsonToProto(jsonSchema, '{"extra": 1}', true).length, 0, 'jsonToProto() can ignore unknown fields');
+
+const scalarTarget = {};
+decodeScalarsInto(pointSchema, encodeWith(pointSchema, { x: 2.5, id: 3, ok: true, label: 'skip' }), scalarTarget);
+assertEqual(scalarTarget.x, 2.5, 'decodeScalarsInto() writes numeric fields');
+assertEqual(scalarTarget.ok, true, 'decodeScalarsInto() writes bool fields');
+assertEqual(scalarTarget.label, undefined, 'decodeScalarsInto() leaves string fields untouched');
+decodeScalarsInto(pointSchema, encodeWith(pointSchema, { id: 4 }), scalarTarget);
+assertEqual(scalarTarget.x, 0, 'decodeScalarsInto() resets absent fields on reuse');
+assertEqual(scalarTarget.id, 4, 'decodeScalarsInto() overwrites the reused target');
+const flatSchema = compileSchema([
+  { name: 'id', fieldNumber: 1, type: 'uint32' },
+  { name: 'score', fieldNumber: 2, type: 'double' },
+  { name: 'deltas', fieldNumber: 3, type: 'sint32', repeated: true },
+]);
+const flat = decodeFlatSmall(flatSchema, encodeWith(flatSchema, { id: 7, score: 0.5, deltas: [-2, 3] }));
+assertEqual(flat.id, 7, 'decodeFlatSmall() decodes varints');
+assertEqual(flat.score, 0.5, 'decodeFlatSmall() decodes fixed-width values');
+assertEqual(flat.deltas.join(','), '-2,3', 'decodeFlatSmall() falls back for packed fields');
+let flatError = '';
+try { decodeFlatSmall(flatSchema, Buffer.from([0x80, 0x01, 0x01])); } catch (e) { flatError = e.message; }
+assert(flatError.includes('multi-byte tag'), 'decodeFlatSmall() rejects multi-byte tags');
+threw = false;
+try { decodeFlatSmall(personSchema, Buffer.alloc(0)); } catch (e) { threw = true; }
+assert(threw, 'decodeFlatSmall() rejects schemas with non-scalar fields');
+
+threw = false;
+try { compileSchema([{ name: 'a', fieldNumber: 1, type: 'nope' }]); } catch (e) { threw = true; }
+assert(threw, 'compileSchema() rejects unknown types');
+
 // Print summary
 console.log('\n=== Test Summary ===\n');
 console.log(`Passed: ${passed}`);